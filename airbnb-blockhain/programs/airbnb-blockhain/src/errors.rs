@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum MathError {
+    #[msg("Arithmetic overflow or underflow")]
+    MathOverflow,
+}
+
+#[error_code]
+pub enum ReservationError {
+    #[msg("Illegal reservation status transition")]
+    InvalidStatusTransition,
+    #[msg("Listing is not active")]
+    ListingInactive,
+    #[msg("End date must be at least one night after start date")]
+    InvalidDateRange,
+    #[msg("Start date is in the past")]
+    StartDateInPast,
+    #[msg("Reservation is too long")]
+    TooManyNights,
+    #[msg("Guest count must be at least one")]
+    InvalidGuestCount,
+    #[msg("Guest count exceeds the listing capacity")]
+    TooManyGuests,
+    #[msg("Some of the requested nights are already booked or blocked")]
+    DatesUnavailable,
+    #[msg("Requested nights are outside the availability window")]
+    OutsideAvailabilityWindow,
+}
+
+#[error_code]
+pub enum ProfileError {
+    #[msg("Only the profile owner can modify it")]
+    UnauthorizedUser,
+    #[msg("Name cannot be empty")]
+    EmptyName,
+    #[msg("Name is too long")]
+    NameTooLong,
+    #[msg("Image URL is too long")]
+    ImageUrlTooLong,
+    #[msg("Image URL must be an https, ipfs or ar URI")]
+    InvalidImageUrl,
+    #[msg("Metadata URI is too long")]
+    MetadataUriTooLong,
+    #[msg("Metadata URI must be an https, ipfs or ar URI")]
+    InvalidMetadataUri,
+    #[msg("Preferred language is too long")]
+    PreferredLanguageTooLong,
+    #[msg("Preferred language must be a language tag such as en or pt-BR")]
+    InvalidPreferredLanguage,
+}
+
+#[error_code]
+pub enum HostError {
+    #[msg("Profile does not have the host role")]
+    MissingHostRole,
+    #[msg("Host account does not belong to this profile")]
+    ProfileMismatch,
+}
+
+#[error_code]
+pub enum GuestError {
+    #[msg("Profile does not have the guest role")]
+    MissingGuestRole,
+    #[msg("Guest account does not belong to this profile")]
+    ProfileMismatch,
+}
+
+#[error_code]
+pub enum ListingError {
+    #[msg("Only the listing's host can modify it")]
+    UnauthorizedHost,
+    #[msg("Title cannot be empty")]
+    EmptyTitle,
+    #[msg("Title is too long")]
+    TitleTooLong,
+    #[msg("Description is too long")]
+    DescriptionTooLong,
+    #[msg("Image URL is too long")]
+    ImageUrlTooLong,
+    #[msg("Image URL must be an https, ipfs or ar URI")]
+    InvalidImageUrl,
+    #[msg("Category is too long")]
+    CategoryTooLong,
+    #[msg("Location value is too long")]
+    LocationTooLong,
+    #[msg("Location value must be an ISO 3166-1 alpha-2 country code")]
+    InvalidCountryCode,
+    #[msg("Room count must be at least one")]
+    InvalidRoomCount,
+    #[msg("Guest count must be at least one")]
+    InvalidGuestCount,
+    #[msg("Price must be greater than zero")]
+    InvalidPrice,
+}
+
+#[error_code]
+pub enum TokenError {
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Transfer fee cannot exceed 10000 bps")]
+    InvalidFeeBps,
+}
@@ -0,0 +1,321 @@
+use anchor_lang::prelude::*;
+
+use crate::states::CancellationPolicy;
+
+// Profiles, hosts and guests
+
+#[event]
+pub struct UserProfileCreated {
+    pub user_profile: Pubkey,
+    pub authority: Pubkey,
+    pub created_at: u64,
+}
+
+#[event]
+pub struct UserProfileUpdated {
+    pub user_profile: Pubkey,
+    pub authority: Pubkey,
+    pub updated_at: u64,
+}
+
+#[event]
+pub struct UserProfileClosed {
+    pub user_profile: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct HostCreated {
+    pub host: Pubkey,
+    pub host_author: Pubkey,
+    pub profile: Pubkey,
+    pub created_at: u64,
+}
+
+#[event]
+pub struct HostClosed {
+    pub host: Pubkey,
+    pub host_author: Pubkey,
+}
+
+#[event]
+pub struct HostMigrated {
+    pub host: Pubkey,
+    pub host_author: Pubkey,
+    pub profile: Pubkey,
+}
+
+#[event]
+pub struct GuestCreated {
+    pub guest: Pubkey,
+    pub guest_author: Pubkey,
+    pub profile: Pubkey,
+    pub created_at: u64,
+}
+
+#[event]
+pub struct GuestClosed {
+    pub guest: Pubkey,
+    pub guest_author: Pubkey,
+}
+
+#[event]
+pub struct GuestMigrated {
+    pub guest: Pubkey,
+    pub guest_author: Pubkey,
+    pub profile: Pubkey,
+}
+
+// Listings
+
+#[event]
+pub struct ListingCreated {
+    pub listing: Pubkey,
+    pub host: Pubkey,
+    pub price: u64,
+    pub guest_count: u8,
+    pub is_active: bool,
+    pub cancellation_policy: CancellationPolicy,
+    pub created_at: u64,
+}
+
+#[event]
+pub struct ListingMigrated {
+    pub listing: Pubkey,
+    pub host: Pubkey,
+    pub cancellation_policy: CancellationPolicy,
+}
+
+#[event]
+pub struct ListingUpdated {
+    pub listing: Pubkey,
+    pub host: Pubkey,
+    pub price: u64,
+    pub guest_count: u8,
+    pub cancellation_policy: CancellationPolicy,
+    pub updated_at: u64,
+}
+
+#[event]
+pub struct ListingActiveSet {
+    pub listing: Pubkey,
+    pub is_active: bool,
+    pub updated_at: u64,
+}
+
+#[event]
+pub struct ListingDatesBlocked {
+    pub listing: Pubkey,
+    pub start_day: u64,
+    pub end_day: u64,
+}
+
+#[event]
+pub struct ListingDatesUnblocked {
+    pub listing: Pubkey,
+    pub start_day: u64,
+    pub end_day: u64,
+}
+
+#[event]
+pub struct ListingClosed {
+    pub listing: Pubkey,
+    pub host: Pubkey,
+}
+
+// Reservations
+
+#[event]
+pub struct ReservationCreated {
+    pub reservation: Pubkey,
+    pub listing: Pubkey,
+    pub guest: Pubkey,
+    pub host: Pubkey,
+    pub start_date: u64,
+    pub end_date: u64,
+    pub guest_count: u8,
+    pub total_nights: u16,
+    pub price_per_night: u64,
+    pub total_price: u64,
+}
+
+#[event]
+pub struct ReservationConfirmed {
+    pub reservation: Pubkey,
+    pub listing: Pubkey,
+    pub guest: Pubkey,
+}
+
+#[event]
+pub struct ReservationCheckedIn {
+    pub reservation: Pubkey,
+    pub listing: Pubkey,
+    pub checked_in_by: Pubkey,
+    pub checked_in_at: u64,
+}
+
+#[event]
+pub struct ReservationCompleted {
+    pub reservation: Pubkey,
+    pub listing: Pubkey,
+    pub completed_at: u64,
+    pub total_bookings: u64,
+}
+
+#[event]
+pub struct ReservationDeclined {
+    pub reservation: Pubkey,
+    pub listing: Pubkey,
+    pub guest: Pubkey,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct ReservationCancelled {
+    pub reservation: Pubkey,
+    pub listing: Pubkey,
+    pub guest: Pubkey,
+    pub refund_bps: u16,
+    pub refund_amount: u64,
+    pub host_amount: u64,
+    pub platform_fee: u64,
+    pub penalty_withheld: u64,
+}
+
+#[event]
+pub struct ReservationCancelledByHost {
+    pub reservation: Pubkey,
+    pub listing: Pubkey,
+    pub guest: Pubkey,
+    pub host: Pubkey,
+    pub refund_amount: u64,
+    pub penalty: u64,
+    pub cancellations: u64,
+}
+
+#[event]
+pub struct ReservationExpired {
+    pub reservation: Pubkey,
+    pub listing: Pubkey,
+    pub guest: Pubkey,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct ReservationClosed {
+    pub reservation: Pubkey,
+    pub guest: Pubkey,
+}
+
+// Platform
+
+#[event]
+pub struct PlatformInitialized {
+    pub platform_config: Pubkey,
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub accepted_mint: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct PlatformConfigUpdated {
+    pub platform_config: Pubkey,
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub accepted_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub release_authority: Pubkey,
+    pub arbiter: Pubkey,
+    pub host_cancel_penalty_bps: u16,
+    pub crank_bounty_bps: u16,
+    pub updated_at: u64,
+}
+
+// Escrows and disputes
+
+#[event]
+pub struct EscrowFunded {
+    pub payment_escrow: Pubkey,
+    pub reservation: Pubkey,
+    pub guest: Pubkey,
+    pub host: Pubkey,
+    pub amount: u64,
+    pub platform_fee: u64,
+    pub release_date: u64,
+}
+
+#[event]
+pub struct EscrowReleased {
+    pub payment_escrow: Pubkey,
+    pub reservation: Pubkey,
+    pub host: Pubkey,
+    pub host_amount: u64,
+    pub platform_fee: u64,
+    pub penalty_withheld: u64,
+}
+
+#[event]
+pub struct CrankBountyPaid {
+    pub payment_escrow: Pubkey,
+    pub cranker: Pubkey,
+    pub bounty: u64,
+}
+
+#[event]
+pub struct EscrowRefunded {
+    pub payment_escrow: Pubkey,
+    pub reservation: Pubkey,
+    pub guest: Pubkey,
+    pub refund_amount: u64,
+    pub platform_fee: u64,
+}
+
+#[event]
+pub struct EscrowClosed {
+    pub payment_escrow: Pubkey,
+    pub guest: Pubkey,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub dispute: Pubkey,
+    pub payment_escrow: Pubkey,
+    pub opened_by: Pubkey,
+    pub reason_hash: [u8; 32],
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub dispute: Pubkey,
+    pub payment_escrow: Pubkey,
+    pub guest_share_bps: u16,
+    pub guest_amount: u64,
+    pub host_amount: u64,
+    pub platform_fee: u64,
+    pub penalty_withheld: u64,
+}
+
+// Tokens
+
+#[event]
+pub struct TokenInitialized {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub fee_bps: u16,
+    pub max_fee: u64,
+}
+
+#[event]
+pub struct TokenMinted {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithheldTokensWithdrawn {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub destination: Pubkey,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::GuestError;
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _close_guest(ctx: Context<CloseGuest>) -> Result<()> {
+    ctx.accounts.user_profile.roles &= !ROLE_GUEST;
+
+    msg!("Guest closed: {}", ctx.accounts.guest.guest_author);
+
+    emit_cpi!(GuestClosed {
+        guest: ctx.accounts.guest.key(),
+        guest_author: ctx.accounts.guest.guest_author,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseGuest<'info> {
+    #[account(mut)]
+    pub guest_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED.as_bytes(), guest_authority.key().as_ref()],
+        bump = user_profile.bump,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    #[account(
+        mut,
+        close = guest_authority,
+        seeds = [GUEST_SEED.as_bytes(), guest_authority.key().as_ref()],
+        bump = guest.bump,
+        constraint = guest.profile == user_profile.key() @ GuestError::ProfileMismatch,
+        // A re-created guest counts reservations from zero again, so none may still exist
+        constraint = guest.open_reservation_count == 0 @ CloseGuestError::GuestHasOpenReservations,
+    )]
+    pub guest: Account<'info, Guest>,
+}
+
+#[error_code]
+pub enum CloseGuestError {
+    #[msg("Guest still has open reservations, close them first")]
+    GuestHasOpenReservations,
+}
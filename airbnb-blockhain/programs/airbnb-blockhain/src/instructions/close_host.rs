@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HostError;
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _close_host(ctx: Context<CloseHost>) -> Result<()> {
+    ctx.accounts.user_profile.roles &= !ROLE_HOST;
+
+    msg!("Host closed: {}", ctx.accounts.host.host_author);
+
+    emit_cpi!(HostClosed {
+        host: ctx.accounts.host.key(),
+        host_author: ctx.accounts.host.host_author,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseHost<'info> {
+    #[account(mut)]
+    pub host_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED.as_bytes(), host_authority.key().as_ref()],
+        bump = user_profile.bump,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    #[account(
+        mut,
+        close = host_authority,
+        seeds = [HOST_SEED.as_bytes(), host_authority.key().as_ref()],
+        bump = host.bump,
+        constraint = host.profile == user_profile.key() @ HostError::ProfileMismatch,
+        constraint = host.active_listing_count == 0 @ CloseHostError::HostHasActiveListings,
+        constraint = host.pending_penalty == 0 @ CloseHostError::HostHasPendingPenalty
+    )]
+    pub host: Account<'info, Host>,
+}
+
+#[error_code]
+pub enum CloseHostError {
+    #[msg("Host still has listings, close them first")]
+    HostHasActiveListings,
+    #[msg("Host still owes a cancellation penalty")]
+    HostHasPendingPenalty,
+}
@@ -1,39 +1,47 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ProfileError;
+use crate::events::*;
 use crate::states::*;
 
 #[inline(never)]
-pub fn _initialize_guest(
-    ctx: Context<InitializeGuest>,
-    name: String,
-    email: String,
-    image_url: String,
-    hashed_password: String,
-    created_at: u64,
-    phone_number: String,
-    date_of_birth: u64,
-    preferred_language: String,
-) -> Result<()> {
+pub fn _initialize_guest(ctx: Context<InitializeGuest>) -> Result<()> {
+    let created_at = Clock::get()?.unix_timestamp as u64;
+
+    // Initialize the guest role of the profile
     let guest = &mut ctx.accounts.guest;
 
     guest.guest_author = ctx.accounts.guest_authority.key();
-    guest.name = name;
-    guest.email = email;
-    guest.image_url = image_url;
-    guest.hashed_password = hashed_password;
+    guest.profile = ctx.accounts.user_profile.key();
     guest.created_at = created_at;
-    guest.phone_number = phone_number;
-    guest.date_of_birth = date_of_birth;
-    guest.preferred_language = preferred_language;
+    guest.reservation_count = 0;
+    guest.open_reservation_count = 0;
     guest.bump = ctx.bumps.guest;
 
+    ctx.accounts.user_profile.roles |= ROLE_GUEST;
+
+    emit_cpi!(GuestCreated {
+        guest: guest.key(),
+        guest_author: guest.guest_author,
+        profile: guest.profile,
+        created_at,
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeGuest<'info> {
     #[account(mut)]
     pub guest_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED.as_bytes(), guest_authority.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == guest_authority.key() @ ProfileError::UnauthorizedUser
+    )]
+    pub user_profile: Account<'info, UserProfile>,
     #[account(
         init,
         payer = guest_authority,
@@ -43,4 +51,4 @@ pub struct InitializeGuest<'info> {
     )]
     pub guest: Account<'info, Guest>,
     pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+}
@@ -1,34 +1,49 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ProfileError;
+use crate::events::*;
 use crate::states::*;
 
 #[inline(never)]
-pub fn _initialize_host(
-    ctx: Context<InitializeHost>,
-    name: String,
-    email: String,
-    image: String,
-    hashed_password: String,
-    created_at: u64,
-) -> Result<()> {
-    // Initialize user
+pub fn _initialize_host(ctx: Context<InitializeHost>) -> Result<()> {
+    let created_at = Clock::get()?.unix_timestamp as u64;
+
+    // Initialize the host role of the profile
     let host = &mut ctx.accounts.host;
-    
+
     host.host_author = ctx.accounts.host_authority.key();
-    host.name = name;
-    host.email = email;
-    host.image = image;
-    host.hashed_password = hashed_password;
+    host.profile = ctx.accounts.user_profile.key();
     host.created_at = created_at;
     host.bump = ctx.bumps.host;
     host.listing_count = 0;
+    host.active_listing_count = 0;
+    host.cancellations = 0;
+    host.last_cancelled_at = 0;
+    host.pending_penalty = 0;
+
+    ctx.accounts.user_profile.roles |= ROLE_HOST;
+
+    emit_cpi!(HostCreated {
+        host: host.key(),
+        host_author: host.host_author,
+        profile: host.profile,
+        created_at,
+    });
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeHost<'info> {
     #[account(mut)]
     pub host_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED.as_bytes(), host_authority.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == host_authority.key() @ ProfileError::UnauthorizedUser
+    )]
+    pub user_profile: Account<'info, UserProfile>,
     #[account(
         init,
         payer = host_authority,
@@ -38,4 +53,4 @@ pub struct InitializeHost<'info> {
     )]
     pub host: Account<'info, Host>,
     pub system_program: Program<'info, System>
-}
\ No newline at end of file
+}
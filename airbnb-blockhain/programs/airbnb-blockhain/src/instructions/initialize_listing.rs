@@ -1,55 +1,101 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::HostError;
+use crate::events::*;
+use crate::math;
 use crate::states::*;
+use crate::validation;
 
 #[inline(never)]
+#[allow(clippy::too_many_arguments)]
 pub fn _initialize_listing(
     ctx: Context<InitializeListing>,
     title: String,
     description: String,
     image_url: String,
-    created_at: u64,
     category: String,
     room_count: u8,
     bathroom_count: u8,
     guest_count: u8,
     location_value: String,
-    total_bookings: u64,
     is_active: bool,
     price: u64,
+    cancellation_policy: CancellationPolicy,
 ) -> Result<()> {
+    validation::listing_title(&title)?;
+    validation::listing_description(&description)?;
+    validation::listing_image_url(&image_url)?;
+    validation::listing_category(&category)?;
+    validation::room_count(room_count)?;
+    validation::guest_count(guest_count)?;
+    validation::location_value(&location_value)?;
+    validation::price(price)?;
+
+    let now = Clock::get()?.unix_timestamp as u64;
     let listing = &mut ctx.accounts.listing;
 
     listing.host = ctx.accounts.host.key();
     listing.title = title;
     listing.description = description;
     listing.image_url = image_url;
-    listing.created_at = created_at;
+    listing.created_at = now;
+    listing.updated_at = now;
     listing.category = category;
     listing.room_count = room_count;
     listing.bathroom_count = bathroom_count;
     listing.guest_count = guest_count;
     listing.location_value = location_value;
-    listing.total_bookings = total_bookings;
+    listing.total_bookings = 0;
     listing.is_active = is_active;
     listing.price = price;
+    listing.cancellation_policy = cancellation_policy;
+    listing.booking_count = 0;
+    listing.open_reservation_count = 0;
+
+    // Empty calendar starting today
+    let mut availability = ctx.accounts.availability.load_init()?;
+    availability.listing = listing.key();
+    availability.window_start_day = now / SECONDS_PER_DAY;
+    availability.bump = ctx.bumps.availability;
     
     // Increment the counter to have unique PDA for listings for a host made
-    ctx.accounts.host.listing_count += 1;
+    let host = &mut ctx.accounts.host;
+    host.listing_count = math::checked_add(host.listing_count, 1)?;
+    host.active_listing_count = math::checked_add(host.active_listing_count, 1)?;
     let clg_counter = &ctx.accounts.host.listing_count;
     msg!("title: {}", listing.title);
     msg!("counter: {}", clg_counter);
+
+    emit_cpi!(ListingCreated {
+        listing: listing.key(),
+        host: listing.host,
+        price: listing.price,
+        guest_count: listing.guest_count,
+        is_active: listing.is_active,
+        cancellation_policy: listing.cancellation_policy.clone(),
+        created_at: listing.created_at,
+    });
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeListing<'info> {
     #[account(mut)]
     listing_authority: Signer<'info>,
 
+    /// Profile of the signer, must hold the host role
+    #[account(
+        seeds = [USER_PROFILE_SEED.as_bytes(), listing_authority.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.has_role(ROLE_HOST) @ HostError::MissingHostRole
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
     #[account(
         mut,
-        constraint = host.host_author == listing_authority.key()
+        constraint = host.host_author == listing_authority.key(),
+        constraint = host.profile == user_profile.key() @ HostError::ProfileMismatch
     )]
     pub host: Account<'info, Host>,
 
@@ -66,5 +112,14 @@ pub struct InitializeListing<'info> {
     )]
     pub listing: Account<'info, Listing>,
 
+    #[account(
+        init,
+        payer = listing_authority,
+        space = 8 + std::mem::size_of::<ListingAvailability>(),
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
@@ -1,47 +1,172 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::{GuestError, ReservationError};
+use crate::events::*;
+use crate::math;
 use crate::states::*;
 
 #[inline(never)]
 pub fn _initialize_reservation(
     ctx: Context<InitializeReservation>,
-    reservation_id: u64,
-    listing: Pubkey,
-    host: Pubkey,
     start_date: u64,
     end_date: u64,
     guest_count: u8,
-    total_nights: u16,
-    price_per_night: u64,
-    total_price: u64,
-    status: ReservationStatus,
-    created_at: u64,
-    payment_status: PaymentStatus,
 ) -> Result<()> {
-    let reservation = &mut ctx.accounts.reservation;
+    create_reservation(
+        &mut ctx.accounts.reservation,
+        &mut ctx.accounts.booking_index,
+        &mut ctx.accounts.guest,
+        &mut ctx.accounts.listing,
+        &ctx.accounts.host,
+        &ctx.accounts.availability,
+        start_date,
+        end_date,
+        guest_count,
+        ctx.bumps.reservation,
+        ctx.bumps.booking_index,
+    )?;
+    let reservation = &ctx.accounts.reservation;
 
-    reservation.guest = ctx.accounts.reservation_authority.key();
-    reservation.listing = listing;
-    reservation.host = host;
+    emit_cpi!(ReservationCreated {
+        reservation: reservation.key(),
+        listing: reservation.listing,
+        guest: reservation.guest,
+        host: reservation.host,
+        start_date,
+        end_date,
+        guest_count,
+        total_nights: reservation.total_nights,
+        price_per_night: reservation.price_per_night,
+        total_price: reservation.total_price,
+    });
+
+    Ok(())
+}
+
+/// Validate a booking against the listing, hold its nights, fill in the new reservation
+/// and its booking index entry, then advance the guest and listing counters
+/// Shared by initialize_reservation and book_listing
+#[allow(clippy::too_many_arguments)]
+pub fn create_reservation<'info>(
+    reservation: &mut Account<'info, Reservation>,
+    booking_index: &mut Account<'info, BookingIndex>,
+    guest: &mut Guest,
+    listing: &mut Account<'info, Listing>,
+    host: &Host,
+    availability: &AccountLoader<'info, ListingAvailability>,
+    start_date: u64,
+    end_date: u64,
+    guest_count: u8,
+    bump: u8,
+    booking_index_bump: u8,
+) -> Result<()> {
+    require!(listing.is_active, ReservationError::ListingInactive);
+    require!(guest_count > 0, ReservationError::InvalidGuestCount);
+    require!(
+        guest_count <= listing.guest_count,
+        ReservationError::TooManyGuests
+    );
+
+    // Nights are counted in whole days, check-in cannot be earlier than now so the
+    // payment and host response deadlines below never start out already expired
+    let now = Clock::get()?.unix_timestamp as u64;
+    let today = now / SECONDS_PER_DAY;
+    let start_day = start_date / SECONDS_PER_DAY;
+    let end_day = end_date / SECONDS_PER_DAY;
+    require!(end_day > start_day, ReservationError::InvalidDateRange);
+    require!(start_date >= now, ReservationError::StartDateInPast);
+    let total_nights = u16::try_from(end_day - start_day)
+        .map_err(|_| ReservationError::TooManyNights)?;
+
+    // Hold the nights so no other reservation can overlap them
+    let mut availability = availability.load_mut()?;
+    availability.advance_window(today);
+    availability.book(start_day, end_day)?;
+
+    // Price always comes from the listing, never from the client
+    let price_per_night = listing.price;
+    let total_price = math::checked_mul(price_per_night, total_nights as u64)?;
+
+    reservation.guest = guest.guest_author;
+    reservation.listing = listing.key();
+    reservation.host = host.host_author;
     reservation.start_date = start_date;
     reservation.end_date = end_date;
     reservation.guest_count = guest_count;
     reservation.total_nights = total_nights;
     reservation.price_per_night = price_per_night;
     reservation.total_price = total_price;
-    reservation.status = status;
-    reservation.created_at = created_at;
-    reservation.payment_status = payment_status;
-    reservation.bump = ctx.bumps.reservation;
+    // Every reservation starts pending, the host confirms or declines it
+    reservation.status = ReservationStatus::Pending;
+    reservation.created_at = now;
+    reservation.updated_at = now;
+    reservation.payment_status = PaymentStatus::Pending;
+    // Anyone can expire the reservation once a deadline passes, both fall before check-in
+    reservation.payment_deadline = math::checked_add(now, PAYMENT_WINDOW_SECONDS)?.min(start_date);
+    reservation.host_response_deadline =
+        math::checked_add(now, HOST_RESPONSE_WINDOW_SECONDS)?.min(start_date);
+    reservation.booking_index = listing.booking_count;
+    // The guest agrees to the policy as it is now, the host can change it for later bookings
+    reservation.cancellation_policy = listing.cancellation_policy.clone();
+    reservation.bump = bump;
+
+    booking_index.listing = listing.key();
+    booking_index.reservation = reservation.key();
+    booking_index.index = listing.booking_count;
+    booking_index.bump = booking_index_bump;
+
+    // Increment the counters to have unique PDAs for the next reservation and index entry
+    guest.reservation_count = math::checked_add(guest.reservation_count, 1)?;
+    guest.open_reservation_count = math::checked_add(guest.open_reservation_count, 1)?;
+    listing.booking_count = math::checked_add(listing.booking_count, 1)?;
+    listing.open_reservation_count = math::checked_add(listing.open_reservation_count, 1)?;
+
+    msg!("Reservation created:");
+    msg!("Nights: {}", total_nights);
+    msg!("Total price: {}", total_price);
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(reservation_id: u64)]
 pub struct InitializeReservation<'info> {
     #[account(mut)]
     pub reservation_authority: Signer<'info>,
+
+    /// Profile of the signer, must hold the guest role
+    #[account(
+        seeds = [USER_PROFILE_SEED.as_bytes(), reservation_authority.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.has_role(ROLE_GUEST) @ GuestError::MissingGuestRole
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// Guest role of the signer, its reservation count seeds the reservation PDA
+    #[account(
+        mut,
+        seeds = [GUEST_SEED.as_bytes(), reservation_authority.key().as_ref()],
+        bump = guest.bump,
+        constraint = guest.profile == user_profile.key() @ GuestError::ProfileMismatch
+    )]
+    pub guest: Account<'info, Guest>,
+
+    /// The listing being booked
+    #[account(mut)]
+    pub listing: Account<'info, Listing>,
+
+    /// Host profile of the listing owner
+    #[account(address = listing.host)]
+    pub host: Account<'info, Host>,
+
+    /// Calendar of the listing being booked
+    #[account(
+        mut,
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump = availability.load()?.bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+
     #[account(
         init,
         payer = reservation_authority,
@@ -49,10 +174,24 @@ pub struct InitializeReservation<'info> {
         seeds = [
             RESERVATION_SEED.as_bytes(),
             reservation_authority.key().as_ref(),
-            &reservation_id.to_le_bytes(),
+            &guest.reservation_count.to_le_bytes(),
         ],
         bump,
     )]
     pub reservation: Account<'info, Reservation>,
+
+    /// Next entry in the listing's booking index
+    #[account(
+        init,
+        payer = reservation_authority,
+        space = 8 + BookingIndex::INIT_SPACE,
+        seeds = [
+            BOOKING_INDEX_SEED.as_bytes(),
+            listing.key().as_ref(),
+            &listing.booking_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub booking_index: Account<'info, BookingIndex>,
     pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ListingError;
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _close_listing(ctx: Context<CloseListing>) -> Result<()> {
+    let host = &mut ctx.accounts.host;
+    host.active_listing_count = host.active_listing_count.saturating_sub(1);
+
+    msg!("Listing closed: {}", ctx.accounts.listing.title);
+
+    emit_cpi!(ListingClosed {
+        listing: ctx.accounts.listing.key(),
+        host: ctx.accounts.listing.host,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseListing<'info> {
+    #[account(mut)]
+    pub listing_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = host.host_author == listing_authority.key() @ ListingError::UnauthorizedHost
+    )]
+    pub host: Account<'info, Host>,
+
+    #[account(
+        mut,
+        close = listing_authority,
+        constraint = listing.host == host.key() @ ListingError::UnauthorizedHost,
+        // Reservations and their booking index entries need the listing until they are closed
+        constraint = listing.open_reservation_count == 0 @ CloseListingError::ListingHasOpenReservations
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// Calendar of the listing, closed along with it
+    #[account(
+        mut,
+        close = listing_authority,
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump = availability.load()?.bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+}
+
+#[error_code]
+pub enum CloseListingError {
+    #[msg("Listing still has open reservations, they must be closed first")]
+    ListingHasOpenReservations,
+}
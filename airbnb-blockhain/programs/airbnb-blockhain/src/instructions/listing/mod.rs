@@ -0,0 +1,9 @@
+pub mod close_listing;
+pub mod set_listing_active;
+pub mod update_listing;
+pub mod update_listing_availability;
+
+pub use close_listing::*;
+pub use set_listing_active::*;
+pub use update_listing::*;
+pub use update_listing_availability::*;
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ListingError;
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _set_listing_active(
+    ctx: Context<SetListingActive>,
+    is_active: bool,
+) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+
+    listing.is_active = is_active;
+    listing.updated_at = Clock::get()?.unix_timestamp as u64;
+
+    msg!("Listing {} active: {}", listing.title, is_active);
+
+    emit_cpi!(ListingActiveSet {
+        listing: listing.key(),
+        is_active,
+        updated_at: listing.updated_at,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetListingActive<'info> {
+    pub listing_authority: Signer<'info>,
+
+    #[account(
+        constraint = host.host_author == listing_authority.key() @ ListingError::UnauthorizedHost
+    )]
+    pub host: Account<'info, Host>,
+
+    #[account(
+        mut,
+        constraint = listing.host == host.key() @ ListingError::UnauthorizedHost
+    )]
+    pub listing: Account<'info, Listing>,
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ListingError;
+use crate::events::*;
+use crate::states::*;
+use crate::validation;
+
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+pub fn _update_listing(
+    ctx: Context<UpdateListing>,
+    title: Option<String>,
+    description: Option<String>,
+    image_url: Option<String>,
+    category: Option<String>,
+    room_count: Option<u8>,
+    bathroom_count: Option<u8>,
+    guest_count: Option<u8>,
+    location_value: Option<String>,
+    price: Option<u64>,
+    cancellation_policy: Option<CancellationPolicy>,
+) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+
+    // Everything is checked before anything is written
+    if let Some(title) = &title {
+        validation::listing_title(title)?;
+    }
+    if let Some(description) = &description {
+        validation::listing_description(description)?;
+    }
+    if let Some(image_url) = &image_url {
+        validation::listing_image_url(image_url)?;
+    }
+    if let Some(category) = &category {
+        validation::listing_category(category)?;
+    }
+    if let Some(room_count) = room_count {
+        validation::room_count(room_count)?;
+    }
+    if let Some(guest_count) = guest_count {
+        validation::guest_count(guest_count)?;
+    }
+    if let Some(location_value) = &location_value {
+        validation::location_value(location_value)?;
+    }
+    if let Some(price) = price {
+        validation::price(price)?;
+    }
+
+    if let Some(title) = title {
+        listing.title = title;
+    }
+    if let Some(description) = description {
+        listing.description = description;
+    }
+    if let Some(image_url) = image_url {
+        listing.image_url = image_url;
+    }
+    if let Some(category) = category {
+        listing.category = category;
+    }
+    if let Some(room_count) = room_count {
+        listing.room_count = room_count;
+    }
+    if let Some(bathroom_count) = bathroom_count {
+        listing.bathroom_count = bathroom_count;
+    }
+    if let Some(guest_count) = guest_count {
+        listing.guest_count = guest_count;
+    }
+    if let Some(location_value) = location_value {
+        listing.location_value = location_value;
+    }
+    if let Some(price) = price {
+        listing.price = price;
+    }
+    if let Some(cancellation_policy) = cancellation_policy {
+        listing.cancellation_policy = cancellation_policy;
+    }
+    listing.updated_at = Clock::get()?.unix_timestamp as u64;
+
+    msg!("Listing updated: {}", listing.title);
+
+    emit_cpi!(ListingUpdated {
+        listing: listing.key(),
+        host: listing.host,
+        price: listing.price,
+        guest_count: listing.guest_count,
+        cancellation_policy: listing.cancellation_policy.clone(),
+        updated_at: listing.updated_at,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateListing<'info> {
+    pub listing_authority: Signer<'info>,
+
+    #[account(
+        constraint = host.host_author == listing_authority.key() @ ListingError::UnauthorizedHost
+    )]
+    pub host: Account<'info, Host>,
+
+    #[account(
+        mut,
+        constraint = listing.host == host.key() @ ListingError::UnauthorizedHost
+    )]
+    pub listing: Account<'info, Listing>,
+}
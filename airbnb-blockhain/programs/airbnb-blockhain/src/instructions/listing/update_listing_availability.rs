@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _block_listing_dates(
+    ctx: Context<UpdateListingAvailability>,
+    start_date: u64,
+    end_date: u64,
+) -> Result<()> {
+    let (start_day, end_day) = night_range(start_date, end_date)?;
+
+    {
+        let mut availability = ctx.accounts.availability.load_mut()?;
+        availability.advance_window(Clock::get()?.unix_timestamp as u64 / SECONDS_PER_DAY);
+        availability.block(start_day, end_day)?;
+    }
+
+    msg!("Blocked nights: {} to {}", start_day, end_day);
+
+    emit_cpi!(ListingDatesBlocked {
+        listing: ctx.accounts.listing.key(),
+        start_day,
+        end_day,
+    });
+    Ok(())
+}
+
+#[inline(never)]
+pub fn _unblock_listing_dates(
+    ctx: Context<UpdateListingAvailability>,
+    start_date: u64,
+    end_date: u64,
+) -> Result<()> {
+    let (start_day, end_day) = night_range(start_date, end_date)?;
+
+    {
+        let mut availability = ctx.accounts.availability.load_mut()?;
+        availability.advance_window(Clock::get()?.unix_timestamp as u64 / SECONDS_PER_DAY);
+        availability.unblock(start_day, end_day)?;
+    }
+
+    msg!("Unblocked nights: {} to {}", start_day, end_day);
+
+    emit_cpi!(ListingDatesUnblocked {
+        listing: ctx.accounts.listing.key(),
+        start_day,
+        end_day,
+    });
+    Ok(())
+}
+
+fn night_range(start_date: u64, end_date: u64) -> Result<(u64, u64)> {
+    let start_day = start_date / SECONDS_PER_DAY;
+    let end_day = end_date / SECONDS_PER_DAY;
+    require!(
+        end_day > start_day,
+        UpdateListingAvailabilityError::InvalidDateRange
+    );
+    Ok((start_day, end_day))
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateListingAvailability<'info> {
+    pub host_authority: Signer<'info>,
+
+    /// Host profile of the listing owner
+    #[account(
+        constraint = host.host_author == host_authority.key() @ UpdateListingAvailabilityError::UnauthorizedHost
+    )]
+    pub host: Account<'info, Host>,
+
+    #[account(
+        constraint = listing.host == host.key() @ UpdateListingAvailabilityError::UnauthorizedHost
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// Calendar of the listing
+    #[account(
+        mut,
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump = availability.load()?.bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+}
+
+#[error_code]
+pub enum UpdateListingAvailabilityError {
+    #[msg("Only the listing's host can change its availability")]
+    UnauthorizedHost,
+    #[msg("End date must be at least one night after start date")]
+    InvalidDateRange,
+}
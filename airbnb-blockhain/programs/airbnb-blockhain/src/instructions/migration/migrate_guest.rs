@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::instructions::migration::rewrite_account;
+use crate::states::*;
+use crate::validation;
+
+#[inline(never)]
+pub fn _migrate_guest(
+    ctx: Context<MigrateGuest>,
+    profile_commitment: [u8; 32],
+    metadata_uri: String,
+    reservation_count: u64,
+) -> Result<()> {
+    validation::metadata_uri(&metadata_uri)?;
+
+    let guest_info = ctx.accounts.guest.to_account_info();
+
+    // Legacy accounts still have the old size, anything else is already migrated
+    require!(
+        guest_info.data_len() == 8 + LegacyGuest::INIT_SPACE,
+        MigrateGuestError::AlreadyMigrated
+    );
+    let legacy = {
+        let data = guest_info.try_borrow_data()?;
+        require!(
+            data[..8] == *Guest::DISCRIMINATOR,
+            MigrateGuestError::InvalidAccount
+        );
+        LegacyGuest::deserialize(&mut &data[8..])?
+    };
+    require_keys_eq!(
+        legacy.guest_author,
+        ctx.accounts.guest_authority.key(),
+        MigrateGuestError::UnauthorizedGuest
+    );
+
+    // The first role migrated creates the profile, a later one keeps what is there
+    let now = Clock::get()?.unix_timestamp as u64;
+    let user_profile = &mut ctx.accounts.user_profile;
+    if user_profile.authority == Pubkey::default() {
+        user_profile.authority = legacy.guest_author;
+        user_profile.name = legacy.name;
+        user_profile.image_url = legacy.image_url;
+        user_profile.profile_commitment = profile_commitment;
+        user_profile.metadata_uri = metadata_uri;
+        user_profile.preferred_language = legacy.preferred_language;
+        user_profile.created_at = legacy.created_at;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+    user_profile.roles |= ROLE_GUEST;
+    user_profile.updated_at = now;
+
+    // Email, password hash, phone number and date of birth are dropped
+    // Legacy reservations were seeded with client ids, the counter resumes above the highest
+    let guest = Guest {
+        guest_author: legacy.guest_author,
+        profile: user_profile.key(),
+        created_at: legacy.created_at,
+        reservation_count,
+        open_reservation_count: 0,
+        bump: legacy.bump,
+    };
+    rewrite_account(
+        &guest_info,
+        &ctx.accounts.guest_authority,
+        &ctx.accounts.system_program,
+        8 + Guest::INIT_SPACE,
+        &guest,
+    )?;
+
+    msg!("Guest migrated: {}", guest.guest_author);
+
+    emit_cpi!(GuestMigrated {
+        guest: guest_info.key(),
+        guest_author: guest.guest_author,
+        profile: guest.profile,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(profile_commitment: [u8; 32], metadata_uri: String, reservation_count: u64)]
+pub struct MigrateGuest<'info> {
+    #[account(mut)]
+    pub guest_authority: Signer<'info>,
+
+    /// Shared profile of the wallet, created by whichever role migrates first
+    #[account(
+        init_if_needed,
+        payer = guest_authority,
+        space = 8 + UserProfile::INIT_SPACE,
+        seeds = [USER_PROFILE_SEED.as_bytes(), guest_authority.key().as_ref()],
+        bump,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// CHECK: Legacy layout no longer deserializes as Guest, the handler checks size and discriminator
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [GUEST_SEED.as_bytes(), guest_authority.key().as_ref()],
+        bump,
+    )]
+    pub guest: UncheckedAccount<'info>,
+
+    /// CHECK: Reservation PDA the counter resumes at, must not exist yet
+    /// `reservation_count` is one above the highest legacy reservation id of the guest
+    #[account(
+        seeds = [
+            RESERVATION_SEED.as_bytes(),
+            guest_authority.key().as_ref(),
+            &reservation_count.to_le_bytes(),
+        ],
+        bump,
+        constraint = next_reservation.data_is_empty() @ MigrateGuestError::ReservationCountInUse,
+    )]
+    pub next_reservation: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum MigrateGuestError {
+    #[msg("Guest account is already migrated")]
+    AlreadyMigrated,
+    #[msg("Account is not a guest account")]
+    InvalidAccount,
+    #[msg("Only the guest can migrate their account")]
+    UnauthorizedGuest,
+    #[msg("A reservation already exists at this reservation count")]
+    ReservationCountInUse,
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::instructions::migration::rewrite_account;
+use crate::states::*;
+use crate::validation;
+
+#[inline(never)]
+pub fn _migrate_host(
+    ctx: Context<MigrateHost>,
+    profile_commitment: [u8; 32],
+    metadata_uri: String,
+) -> Result<()> {
+    validation::metadata_uri(&metadata_uri)?;
+
+    let host_info = ctx.accounts.host.to_account_info();
+
+    // Legacy accounts still have the old size, anything else is already migrated
+    require!(
+        host_info.data_len() == 8 + LegacyHost::INIT_SPACE,
+        MigrateHostError::AlreadyMigrated
+    );
+    let legacy = {
+        let data = host_info.try_borrow_data()?;
+        require!(
+            data[..8] == *Host::DISCRIMINATOR,
+            MigrateHostError::InvalidAccount
+        );
+        LegacyHost::deserialize(&mut &data[8..])?
+    };
+    require_keys_eq!(
+        legacy.host_author,
+        ctx.accounts.host_authority.key(),
+        MigrateHostError::UnauthorizedHost
+    );
+
+    // The first role migrated creates the profile, a later one keeps what is there
+    let now = Clock::get()?.unix_timestamp as u64;
+    let user_profile = &mut ctx.accounts.user_profile;
+    if user_profile.authority == Pubkey::default() {
+        user_profile.authority = legacy.host_author;
+        user_profile.name = legacy.name;
+        user_profile.image_url = legacy.image;
+        user_profile.profile_commitment = profile_commitment;
+        user_profile.metadata_uri = metadata_uri;
+        user_profile.created_at = legacy.created_at;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+    user_profile.roles |= ROLE_HOST;
+    user_profile.updated_at = now;
+
+    // Email and password hash are dropped
+    // Legacy listings count as active once migrate_listing has given them a calendar
+    let host = Host {
+        host_author: legacy.host_author,
+        profile: user_profile.key(),
+        created_at: legacy.created_at,
+        listing_count: legacy.listing_count,
+        active_listing_count: 0,
+        cancellations: 0,
+        last_cancelled_at: 0,
+        pending_penalty: 0,
+        bump: legacy.bump,
+    };
+    rewrite_account(
+        &host_info,
+        &ctx.accounts.host_authority,
+        &ctx.accounts.system_program,
+        8 + Host::INIT_SPACE,
+        &host,
+    )?;
+
+    msg!("Host migrated: {}", host.host_author);
+
+    emit_cpi!(HostMigrated {
+        host: host_info.key(),
+        host_author: host.host_author,
+        profile: host.profile,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateHost<'info> {
+    #[account(mut)]
+    pub host_authority: Signer<'info>,
+
+    /// Shared profile of the wallet, created by whichever role migrates first
+    #[account(
+        init_if_needed,
+        payer = host_authority,
+        space = 8 + UserProfile::INIT_SPACE,
+        seeds = [USER_PROFILE_SEED.as_bytes(), host_authority.key().as_ref()],
+        bump,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// CHECK: Legacy layout no longer deserializes as Host, the handler checks size and discriminator
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [HOST_SEED.as_bytes(), host_authority.key().as_ref()],
+        bump,
+    )]
+    pub host: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum MigrateHostError {
+    #[msg("Host account is already migrated")]
+    AlreadyMigrated,
+    #[msg("Account is not a host account")]
+    InvalidAccount,
+    #[msg("Only the host can migrate their account")]
+    UnauthorizedHost,
+}
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HostError;
+use crate::events::*;
+use crate::instructions::migration::rewrite_account;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _migrate_listing(
+    ctx: Context<MigrateListing>,
+    cancellation_policy: CancellationPolicy,
+) -> Result<()> {
+    let listing_info = ctx.accounts.listing.to_account_info();
+
+    // Legacy accounts still have the old size, anything else is already migrated
+    require!(
+        listing_info.data_len() == 8 + LegacyListing::INIT_SPACE,
+        MigrateListingError::AlreadyMigrated
+    );
+    let legacy = {
+        let data = listing_info.try_borrow_data()?;
+        require!(
+            data[..8] == *Listing::DISCRIMINATOR,
+            MigrateListingError::InvalidAccount
+        );
+        LegacyListing::deserialize(&mut &data[8..])?
+    };
+    require_keys_eq!(
+        legacy.host,
+        ctx.accounts.host.key(),
+        MigrateListingError::UnauthorizedHost
+    );
+
+    // Legacy reservations never went through the booking index or the open count
+    let now = Clock::get()?.unix_timestamp as u64;
+    let listing = Listing {
+        host: legacy.host,
+        title: legacy.title,
+        description: legacy.description,
+        image_url: legacy.image_url,
+        created_at: legacy.created_at,
+        updated_at: now,
+        category: legacy.category,
+        room_count: legacy.room_count,
+        bathroom_count: legacy.bathroom_count,
+        guest_count: legacy.guest_count,
+        location_value: legacy.location_value,
+        total_bookings: legacy.total_bookings,
+        is_active: legacy.is_active,
+        price: legacy.price,
+        cancellation_policy,
+        booking_count: 0,
+        open_reservation_count: 0,
+    };
+    rewrite_account(
+        &listing_info,
+        &ctx.accounts.listing_authority,
+        &ctx.accounts.system_program,
+        8 + Listing::INIT_SPACE,
+        &listing,
+    )?;
+
+    // Empty calendar starting today
+    let mut availability = ctx.accounts.availability.load_init()?;
+    availability.listing = listing_info.key();
+    availability.window_start_day = now / SECONDS_PER_DAY;
+    availability.bump = ctx.bumps.availability;
+
+    let host = &mut ctx.accounts.host;
+    host.active_listing_count = math::checked_add(host.active_listing_count, 1)?;
+
+    msg!("Listing migrated: {}", listing_info.key());
+
+    emit_cpi!(ListingMigrated {
+        listing: listing_info.key(),
+        host: listing.host,
+        cancellation_policy: listing.cancellation_policy,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateListing<'info> {
+    #[account(mut)]
+    pub listing_authority: Signer<'info>,
+
+    /// Profile of the signer, must hold the host role
+    #[account(
+        seeds = [USER_PROFILE_SEED.as_bytes(), listing_authority.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.has_role(ROLE_HOST) @ HostError::MissingHostRole
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// Host has to be migrated first so it deserializes here
+    #[account(
+        mut,
+        seeds = [HOST_SEED.as_bytes(), listing_authority.key().as_ref()],
+        bump = host.bump,
+        constraint = host.profile == user_profile.key() @ HostError::ProfileMismatch
+    )]
+    pub host: Account<'info, Host>,
+
+    /// CHECK: Legacy layout no longer deserializes as Listing, the handler checks size, discriminator and host
+    #[account(mut, owner = crate::ID)]
+    pub listing: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = listing_authority,
+        space = 8 + std::mem::size_of::<ListingAvailability>(),
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum MigrateListingError {
+    #[msg("Listing account is already migrated")]
+    AlreadyMigrated,
+    #[msg("Account is not a listing account")]
+    InvalidAccount,
+    #[msg("Only the listing's host can migrate it")]
+    UnauthorizedHost,
+}
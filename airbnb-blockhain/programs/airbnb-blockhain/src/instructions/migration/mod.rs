@@ -0,0 +1,9 @@
+pub mod migrate_guest;
+pub mod migrate_host;
+pub mod migrate_listing;
+pub mod rewrite_account;
+
+pub use migrate_guest::*;
+pub use migrate_host::*;
+pub use migrate_listing::*;
+pub use rewrite_account::*;
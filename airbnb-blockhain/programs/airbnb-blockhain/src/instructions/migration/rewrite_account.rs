@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Wipe a program-owned account, resize it to `space` and write `value` in its place
+/// Rent is topped up from `payer` when the account grows and refunded to it when it shrinks
+pub fn rewrite_account<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    value: &T,
+) -> Result<()> {
+    // Nothing from the old layout may survive the resize
+    account.try_borrow_mut_data()?.fill(0);
+    account.resize(space)?;
+
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+    let balance = account.lamports();
+    if balance > rent_exempt {
+        let refund = balance - rent_exempt;
+        **account.try_borrow_mut_lamports()? -= refund;
+        **payer.to_account_info().try_borrow_mut_lamports()? += refund;
+    } else if balance < rent_exempt {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent_exempt - balance,
+        )?;
+    }
+
+    let mut data = account.try_borrow_mut_data()?;
+    value.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
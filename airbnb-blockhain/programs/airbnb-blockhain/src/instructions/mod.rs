@@ -10,8 +10,29 @@ pub mod initialize_guest;
 pub use initialize_reservation::*;
 pub mod initialize_reservation;
 
+pub use close_host::*;
+pub mod close_host;
+
+pub use close_guest::*;
+pub mod close_guest;
+
+pub mod profile;
+pub use profile::*;
+
+pub mod listing;
+pub use listing::*;
+
+pub mod reservation;
+pub use reservation::*;
+
+pub mod platform;
+pub use platform::*;
+
 pub mod payment;
 pub use payment::*;
 
 pub mod token;
-pub use token::*;
\ No newline at end of file
+pub use token::*;
+
+pub mod migration;
+pub use migration::*;
\ No newline at end of file
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::token::close_escrow_vault;
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _close_payment_escrow(ctx: Context<ClosePaymentEscrow>) -> Result<()> {
+    // Rent of the vault goes back to the guest who paid for it
+    close_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.payment_escrow,
+        &ctx.accounts.mint,
+        &ctx.accounts.guest_authority.to_account_info(),
+    )?;
+
+    // The escrow is settled, so its dispute was resolved: close it too, rent back to whoever opened it
+    if let Some(linked_dispute) = ctx.accounts.payment_escrow.dispute {
+        let (Some(dispute), Some(dispute_opener)) =
+            (&ctx.accounts.dispute, &ctx.accounts.dispute_opener)
+        else {
+            return err!(ClosePaymentEscrowError::MissingDispute);
+        };
+        require_keys_eq!(
+            dispute.key(),
+            linked_dispute,
+            ClosePaymentEscrowError::InvalidDispute
+        );
+        require_keys_eq!(
+            dispute_opener.key(),
+            dispute.opened_by,
+            ClosePaymentEscrowError::InvalidDisputeOpener
+        );
+        dispute.close(dispute_opener.to_account_info())?;
+    }
+
+    msg!("Payment escrow closed: {}", ctx.accounts.payment_escrow.key());
+
+    emit_cpi!(EscrowClosed {
+        payment_escrow: ctx.accounts.payment_escrow.key(),
+        guest: ctx.accounts.payment_escrow.guest,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePaymentEscrow<'info> {
+    /// Guest who funded the escrow (original payer)
+    #[account(mut)]
+    pub guest_authority: Signer<'info>,
+
+    /// Only settled escrows can be closed
+    #[account(
+        mut,
+        close = guest_authority,
+        constraint = payment_escrow.guest == guest_authority.key() @ ClosePaymentEscrowError::UnauthorizedGuest,
+        constraint = payment_escrow.status == EscrowStatus::Released
+            || payment_escrow.status == EscrowStatus::Refunded @ ClosePaymentEscrowError::EscrowNotSettled
+    )]
+    pub payment_escrow: Account<'info, PaymentEscrow>,
+
+    /// Vault of the escrow, must be empty
+    #[account(
+        mut,
+        seeds = [ESCROW_VAULT_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump = payment_escrow.vault_bump,
+        constraint = escrow_vault.amount == 0 @ ClosePaymentEscrowError::VaultNotEmpty
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The payment token mint (receives withheld transfer fees)
+    #[account(
+        mut,
+        address = escrow_vault.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Dispute raised on the escrow, if any
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Option<Account<'info, Dispute>>,
+
+    /// Guest or host who opened the dispute, receives its rent back
+    /// CHECK: Only receives lamports, must match the dispute's opened_by
+    #[account(mut)]
+    pub dispute_opener: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[error_code]
+pub enum ClosePaymentEscrowError {
+    #[msg("Only the guest who funded the escrow can close it")]
+    UnauthorizedGuest,
+    #[msg("Escrow has not been released or refunded")]
+    EscrowNotSettled,
+    #[msg("Escrow vault still holds tokens")]
+    VaultNotEmpty,
+    #[msg("The escrow's dispute and the account that opened it must be provided")]
+    MissingDispute,
+    #[msg("Dispute does not belong to this escrow")]
+    InvalidDispute,
+    #[msg("Rent of the dispute must go back to whoever opened it")]
+    InvalidDisputeOpener,
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::payment::{pay_host_from_escrow, ReleasePaymentEscrowError};
+use crate::instructions::token::transfer_from_escrow_vault;
+use crate::events::*;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _crank_release_payment_escrow(
+    ctx: Context<CrankReleasePaymentEscrow>,
+) -> Result<()> {
+    let platform_config = &ctx.accounts.platform_config;
+    let decimals = platform_config.mint_decimals;
+
+    // Same conditions as the operator release: funded, not disputed, stay started, release date passed
+    let settlement = pay_host_from_escrow(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.host_token_account,
+        &ctx.accounts.payment_escrow,
+        &ctx.accounts.reservation,
+        &mut ctx.accounts.host,
+        &ctx.accounts.mint,
+        decimals,
+    )?;
+
+    // The cranker's bounty comes out of the platform fee, never out of the host's share
+    let bounty = math::bps_of(
+        ctx.accounts.payment_escrow.platform_fee,
+        platform_config.crank_bounty_bps,
+    )?
+    .min(settlement.remaining);
+    transfer_from_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.cranker_token_account,
+        &ctx.accounts.payment_escrow,
+        &ctx.accounts.mint,
+        bounty,
+        decimals,
+    )?;
+
+    // Sweep whatever is left in the vault (the platform fee) to the treasury
+    let platform_fee_swept = math::checked_sub(settlement.remaining, bounty)?;
+    transfer_from_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.platform_treasury,
+        &ctx.accounts.payment_escrow,
+        &ctx.accounts.mint,
+        platform_fee_swept,
+        decimals,
+    )?;
+
+    let payment_escrow = &mut ctx.accounts.payment_escrow;
+    payment_escrow.set_status(EscrowStatus::Released)?;
+    ctx.accounts.platform_config.escrow_settled()?;
+
+    msg!("Payment escrow released by crank:");
+    msg!("Crank bounty: {}", bounty);
+    msg!("Platform fee swept to treasury: {}", platform_fee_swept);
+    msg!("Host: {}", payment_escrow.host);
+
+    emit_cpi!(EscrowReleased {
+        payment_escrow: payment_escrow.key(),
+        reservation: payment_escrow.reservation,
+        host: payment_escrow.host,
+        host_amount: settlement.host_amount,
+        platform_fee: platform_fee_swept,
+        penalty_withheld: settlement.penalty_withheld,
+    });
+    emit_cpi!(CrankBountyPaid {
+        payment_escrow: payment_escrow.key(),
+        cranker: ctx.accounts.cranker.key(),
+        bounty,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CrankReleasePaymentEscrow<'info> {
+    /// Anyone can crank a release that is due
+    pub cranker: Signer<'info>,
+
+    /// Global platform configuration (mint, treasury, crank bounty)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// The escrow account to release
+    #[account(
+        mut,
+        seeds = [
+            PAYMENT_ESCROW_SEED.as_bytes(),
+            payment_escrow.reservation.as_ref(),
+            &payment_escrow.escrow_id.to_le_bytes(),
+        ],
+        bump = payment_escrow.bump,
+    )]
+    pub payment_escrow: Box<Account<'info, PaymentEscrow>>,
+
+    /// The reservation this escrow is for
+    #[account(
+        address = payment_escrow.reservation @ ReleasePaymentEscrowError::InvalidReservation
+    )]
+    pub reservation: Box<Account<'info, Reservation>>,
+
+    /// Host profile of the payee, outstanding cancellation penalties are withheld
+    #[account(
+        mut,
+        seeds = [HOST_SEED.as_bytes(), payment_escrow.host.as_ref()],
+        bump = host.bump,
+    )]
+    pub host: Box<Account<'info, Host>>,
+
+    /// Vault holding the escrowed funds (source of payment)
+    #[account(
+        mut,
+        seeds = [ESCROW_VAULT_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump = payment_escrow.vault_bump,
+    )]
+    pub escrow_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ ReleasePaymentEscrowError::InvalidMint
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Platform treasury account (receives the platform fee)
+    #[account(
+        mut,
+        address = platform_config.treasury @ ReleasePaymentEscrowError::InvalidTreasury
+    )]
+    pub platform_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Host's token account (destination of payment)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payment_escrow.host,
+        associated_token::token_program = token_program
+    )]
+    pub host_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token account of the cranker's choosing that receives the bounty
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub cranker_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
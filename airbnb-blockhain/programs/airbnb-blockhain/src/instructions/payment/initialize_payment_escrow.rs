@@ -5,54 +5,136 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount},
 };
 
-use crate::instructions::token::transfer_to_platform_treasury;
+use crate::instructions::token::transfer_to_escrow_vault;
+use crate::events::*;
+use crate::math;
 use crate::states::*;
 
 #[inline(never)]
 pub fn _initialize_payment_escrow(
     ctx: Context<InitializePaymentEscrow>,
-    _escrow_id: u64, // Used in PDA generation via #[instruction]
+    escrow_id: u64,
     amount: u64,
     release_date: u64,
 ) -> Result<()> {
     // TODO: add explicit balance checks
-    let payment_escrow = &mut ctx.accounts.payment_escrow;
-    let reservation = &ctx.accounts.reservation;
-
-    // Fixed 5% platform fee (500 basis points)
-    let platform_fee = (amount * 500) / 10000; // 5% fee
-
-    // Initialize escrow account
-    payment_escrow.reservation = reservation.key();
-    payment_escrow.guest = ctx.accounts.guest_authority.key();
-    payment_escrow.host = reservation.host;
-    payment_escrow.amount = amount;
-    payment_escrow.platform_fee = platform_fee;
-    payment_escrow.status = EscrowStatus::Funded;
-    payment_escrow.created_at = Clock::get()?.unix_timestamp as u64;
-    payment_escrow.release_date = release_date;
-    payment_escrow.bump = ctx.bumps.payment_escrow;
+    let guest = ctx.accounts.guest_authority.key();
+    let (bump, vault_bump) = (ctx.bumps.payment_escrow, ctx.bumps.escrow_vault);
+    let platform_fee = fill_payment_escrow(
+        &mut ctx.accounts.payment_escrow,
+        &mut ctx.accounts.reservation,
+        &mut ctx.accounts.platform_config,
+        guest,
+        escrow_id,
+        amount,
+        release_date,
+        bump,
+        vault_bump,
+    )?;
+    let platform_config = &ctx.accounts.platform_config;
 
-    // Transfer tokens from guest to platform treasury (immediate fee separation)
-    transfer_to_platform_treasury(
+    // Transfer tokens from guest to the escrow vault (held until release or refund)
+    transfer_to_escrow_vault(
         &ctx.accounts.token_program,
         &ctx.accounts.guest_token_account,
-        &ctx.accounts.platform_treasury,
+        &ctx.accounts.escrow_vault,
         &ctx.accounts.guest_authority,
         &ctx.accounts.mint,
         amount,
+        platform_config.mint_decimals,
     )?;
 
     msg!("Payment escrow initialized:");
     msg!("Amount: {}", amount);
-    msg!("Platform fee: {} (5%)", platform_fee);
-    msg!("Host amount: {}", amount - platform_fee);
+    msg!("Platform fee: {} ({} bps)", platform_fee, platform_config.fee_bps);
     msg!("Release date: {}", release_date);
-    msg!("Tokens transferred to platform treasury");
+    msg!("Tokens transferred to escrow vault");
+
+    emit_cpi!(EscrowFunded {
+        payment_escrow: ctx.accounts.payment_escrow.key(),
+        reservation: ctx.accounts.reservation.key(),
+        guest: ctx.accounts.guest_authority.key(),
+        host: ctx.accounts.reservation.host,
+        amount,
+        platform_fee,
+        release_date,
+    });
 
     Ok(())
 }
 
+/// Check the reservation can be paid, fill in the new escrow and link it into the reservation
+/// Shared by initialize_payment_escrow and book_listing, the caller moves the funds
+/// Returns the platform fee
+#[allow(clippy::too_many_arguments)]
+pub fn fill_payment_escrow<'info>(
+    payment_escrow: &mut Account<'info, PaymentEscrow>,
+    reservation: &mut Account<'info, Reservation>,
+    platform_config: &mut PlatformConfig,
+    guest: Pubkey,
+    escrow_id: u64,
+    amount: u64,
+    release_date: u64,
+    bump: u8,
+    vault_bump: u8,
+) -> Result<u64> {
+    // Only live reservations can be paid for, and only for their full price
+    require!(
+        matches!(
+            reservation.status,
+            ReservationStatus::Pending | ReservationStatus::Confirmed
+        ),
+        InitializePaymentEscrowError::ReservationNotPayable
+    );
+    require!(
+        amount == reservation.total_price,
+        InitializePaymentEscrowError::AmountMismatch
+    );
+    // The host cannot be paid before the stay is over
+    require!(
+        release_date >= reservation.end_date,
+        InitializePaymentEscrowError::ReleaseBeforeCheckOut
+    );
+
+    // At most one active escrow, a new one is only allowed once the previous was refunded
+    require!(
+        reservation.payment_escrow.is_none()
+            || reservation.payment_status == PaymentStatus::Refunded,
+        InitializePaymentEscrowError::EscrowAlreadyActive
+    );
+
+    // Platform fee in basis points, taken from the platform config
+    let (platform_fee, host_amount) = math::split_platform_fee(amount, platform_config.fee_bps)?;
+
+    // Initialize escrow account
+    payment_escrow.escrow_id = escrow_id;
+    payment_escrow.reservation = reservation.key();
+    payment_escrow.guest = guest;
+    payment_escrow.host = reservation.host;
+    payment_escrow.amount = amount;
+    payment_escrow.platform_fee = platform_fee;
+    let now = Clock::get()?.unix_timestamp as u64;
+    payment_escrow.status = EscrowStatus::Funded;
+    payment_escrow.created_at = now;
+    payment_escrow.updated_at = now;
+    payment_escrow.release_date = release_date;
+    payment_escrow.dispute = None;
+    payment_escrow.bump = bump;
+    payment_escrow.vault_bump = vault_bump;
+    platform_config.escrow_opened()?;
+
+    // Link the escrow back into the reservation
+    reservation.payment_escrow = Some(payment_escrow.key());
+    reservation.token_amount = amount;
+    reservation.platform_fee = platform_fee;
+    reservation.set_payment_status(PaymentStatus::Paid)?;
+
+    msg!("Host amount: {}", host_amount);
+
+    Ok(platform_fee)
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(escrow_id: u64)] // Unique ID for this escrow
 pub struct InitializePaymentEscrow<'info> {
@@ -61,6 +143,7 @@ pub struct InitializePaymentEscrow<'info> {
     
     /// The reservation this escrow is for
     #[account(
+        mut,
         constraint = reservation.guest == guest_authority.key() @ InitializePaymentEscrowError::UnauthorizedGuest
     )]
     pub reservation: Account<'info, Reservation>,
@@ -79,11 +162,18 @@ pub struct InitializePaymentEscrow<'info> {
     )]
     pub payment_escrow: Account<'info, PaymentEscrow>,
     
+    /// Global platform configuration (fee, mint)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    
     /// The payment token mint
-    /// TODO: add the correct constraint
-    // #[account(
-    //     constraint = mint.key() == platform_treasury.mint @ InitializePaymentEscrowError::InvalidMint
-    // )]
+    #[account(
+        address = platform_config.accepted_mint @ InitializePaymentEscrowError::InvalidMint
+    )]
     pub mint: InterfaceAccount<'info, Mint>,
     
     /// Guest's token account (source of payment)
@@ -95,12 +185,17 @@ pub struct InitializePaymentEscrow<'info> {
     )]
     pub guest_token_account: InterfaceAccount<'info, TokenAccount>,
     
-    /// Platform treasury account (receives all payments)
+    /// Vault holding this booking's funds, owned by the escrow PDA
     #[account(
-        mut,
-        constraint = platform_treasury.mint == mint.key() @ InitializePaymentEscrowError::InvalidTreasuryMint
+        init,
+        payer = guest_authority,
+        seeds = [ESCROW_VAULT_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = payment_escrow,
+        token::token_program = token_program,
     )]
-    pub platform_treasury: InterfaceAccount<'info, TokenAccount>,
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token2022>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -113,6 +208,12 @@ pub enum InitializePaymentEscrowError {
     UnauthorizedGuest,
     #[msg("Invalid mint provided")]
     InvalidMint,
-    #[msg("Platform treasury mint does not match payment mint")]
-    InvalidTreasuryMint,
+    #[msg("Escrow amount must equal the reservation total price")]
+    AmountMismatch,
+    #[msg("Reservation already has an active escrow")]
+    EscrowAlreadyActive,
+    #[msg("Reservation is cancelled or completed")]
+    ReservationNotPayable,
+    #[msg("Release date is before the reservation end date")]
+    ReleaseBeforeCheckOut,
 }
\ No newline at end of file
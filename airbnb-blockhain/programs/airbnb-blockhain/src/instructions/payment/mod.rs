@@ -1,5 +1,15 @@
 pub mod initialize_payment_escrow;
 pub mod release_payment_escrow;
+pub mod crank_release_payment_escrow;
+pub mod refund_payment_escrow;
+pub mod open_dispute;
+pub mod resolve_dispute;
+pub mod close_payment_escrow;
 
 pub use initialize_payment_escrow::*;
 pub use release_payment_escrow::*;
+pub use crank_release_payment_escrow::*;
+pub use refund_payment_escrow::*;
+pub use open_dispute::*;
+pub use resolve_dispute::*;
+pub use close_payment_escrow::*;
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+use crate::validation;
+
+#[inline(never)]
+pub fn _open_dispute(
+    ctx: Context<OpenDispute>,
+    reason_hash: [u8; 32],
+    evidence_uris: Vec<String>,
+) -> Result<()> {
+    require!(
+        evidence_uris.len() <= MAX_EVIDENCE_URIS,
+        OpenDisputeError::TooManyEvidenceUris
+    );
+    require!(
+        evidence_uris.iter().all(|uri| uri.len() <= MAX_EVIDENCE_URI_LEN),
+        OpenDisputeError::EvidenceUriTooLong
+    );
+    require!(
+        evidence_uris.iter().all(|uri| validation::is_uri(uri)),
+        OpenDisputeError::InvalidEvidenceUri
+    );
+
+    let dispute = &mut ctx.accounts.dispute;
+    let payment_escrow = &mut ctx.accounts.payment_escrow;
+
+    dispute.payment_escrow = payment_escrow.key();
+    dispute.reservation = payment_escrow.reservation;
+    dispute.opened_by = ctx.accounts.dispute_authority.key();
+    dispute.reason_hash = reason_hash;
+    dispute.evidence_uris = evidence_uris;
+    dispute.status = DisputeStatus::Open;
+    dispute.opened_at = Clock::get()?.unix_timestamp as u64;
+    dispute.resolved_at = 0;
+    dispute.guest_share_bps = 0;
+    dispute.bump = ctx.bumps.dispute;
+
+    // Freeze the escrow until the arbiter resolves it
+    payment_escrow.dispute = Some(dispute.key());
+    payment_escrow.set_status(EscrowStatus::Disputed)?;
+
+    msg!("Dispute opened:");
+    msg!("Escrow: {}", dispute.payment_escrow);
+    msg!("Opened by: {}", dispute.opened_by);
+    msg!("Evidence URIs: {}", dispute.evidence_uris.len());
+
+    emit_cpi!(DisputeOpened {
+        dispute: dispute.key(),
+        payment_escrow: dispute.payment_escrow,
+        opened_by: dispute.opened_by,
+        reason_hash,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    /// Guest or host of the escrow
+    #[account(
+        mut,
+        constraint = dispute_authority.key() == payment_escrow.guest
+            || dispute_authority.key() == payment_escrow.host @ OpenDisputeError::UnauthorizedParty
+    )]
+    pub dispute_authority: Signer<'info>,
+
+    /// The escrow being disputed (must not be released yet)
+    #[account(
+        mut,
+        constraint = payment_escrow.status == EscrowStatus::Funded @ OpenDisputeError::EscrowNotFunded
+    )]
+    pub payment_escrow: Account<'info, PaymentEscrow>,
+
+    /// The dispute record (one per escrow)
+    #[account(
+        init,
+        payer = dispute_authority,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [DISPUTE_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum OpenDisputeError {
+    #[msg("Only the guest or host of the escrow can open a dispute")]
+    UnauthorizedParty,
+    #[msg("Escrow is not in funded status")]
+    EscrowNotFunded,
+    #[msg("Too many evidence URIs")]
+    TooManyEvidenceUris,
+    #[msg("Evidence URI is too long")]
+    EvidenceUriTooLong,
+    #[msg("Evidence URIs must be https, ipfs or ar URIs")]
+    InvalidEvidenceUri,
+}
@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::token::transfer_from_escrow_vault;
+use crate::events::*;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _refund_payment_escrow(
+    ctx: Context<RefundPaymentEscrow>,
+) -> Result<()> {
+    let payment_escrow = &ctx.accounts.payment_escrow;
+    let platform_config = &ctx.accounts.platform_config;
+
+    // Released funds already left the vault
+    require!(
+        payment_escrow.status != EscrowStatus::Released,
+        RefundPaymentEscrowError::EscrowAlreadyReleased
+    );
+    require!(
+        payment_escrow.status != EscrowStatus::Disputed,
+        RefundPaymentEscrowError::EscrowDisputed
+    );
+    require!(
+        payment_escrow.status == EscrowStatus::Funded,
+        RefundPaymentEscrowError::EscrowNotFunded
+    );
+
+    // Refund amount depends on whether the platform keeps its fee
+    let refund_amount = match platform_config.refund_fee_policy {
+        RefundFeePolicy::RetainPlatformFee => {
+            math::checked_sub(payment_escrow.amount, payment_escrow.platform_fee)?
+        }
+        RefundFeePolicy::RefundPlatformFee => payment_escrow.amount,
+    };
+
+    // Never send more than the vault holds
+    let vault_balance = ctx.accounts.escrow_vault.amount;
+    let refund_amount = refund_amount.min(vault_balance);
+
+    // Return the refund to the guest
+    transfer_from_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.guest_token_account,
+        payment_escrow,
+        &ctx.accounts.mint,
+        refund_amount,
+        platform_config.mint_decimals,
+    )?;
+
+    // Sweep whatever is left in the vault (the retained fee) to the treasury
+    let platform_fee_swept = math::checked_sub(vault_balance, refund_amount)?;
+    transfer_from_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.platform_treasury,
+        payment_escrow,
+        &ctx.accounts.mint,
+        platform_fee_swept,
+        platform_config.mint_decimals,
+    )?;
+
+    // Update escrow and reservation status
+    ctx.accounts.payment_escrow.set_status(EscrowStatus::Refunded)?;
+    ctx.accounts.platform_config.escrow_settled()?;
+    ctx.accounts.reservation.set_payment_status(PaymentStatus::Refunded)?;
+
+    msg!("Payment escrow refunded:");
+    msg!("Refund amount: {}", refund_amount);
+    msg!("Platform fee swept to treasury: {}", platform_fee_swept);
+    msg!("Guest: {}", ctx.accounts.payment_escrow.guest);
+
+    emit_cpi!(EscrowRefunded {
+        payment_escrow: ctx.accounts.payment_escrow.key(),
+        reservation: ctx.accounts.payment_escrow.reservation,
+        guest: ctx.accounts.payment_escrow.guest,
+        refund_amount,
+        platform_fee: platform_fee_swept,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundPaymentEscrow<'info> {
+    /// Platform authority (can refund payments)
+    #[account(
+        mut,
+        address = platform_config.release_authority @ RefundPaymentEscrowError::UnauthorizedReleaseAuthority
+    )]
+    pub platform_authority: Signer<'info>,
+
+    /// Global platform configuration (mint, treasury, refund fee policy)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// The escrow account to refund
+    #[account(mut)]
+    pub payment_escrow: Account<'info, PaymentEscrow>,
+
+    /// The reservation this escrow is for
+    #[account(
+        mut,
+        address = payment_escrow.reservation @ RefundPaymentEscrowError::InvalidReservation
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    /// Vault holding the escrowed funds (source of refund)
+    #[account(
+        mut,
+        seeds = [ESCROW_VAULT_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump = payment_escrow.vault_bump,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ RefundPaymentEscrowError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform treasury account (receives any retained fee)
+    #[account(
+        mut,
+        address = platform_config.treasury @ RefundPaymentEscrowError::InvalidTreasury
+    )]
+    pub platform_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Guest's token account (destination of refund)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payment_escrow.guest,
+        associated_token::token_program = token_program
+    )]
+    pub guest_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum RefundPaymentEscrowError {
+    #[msg("Escrow has already been released to the host")]
+    EscrowAlreadyReleased,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Escrow is not in funded status")]
+    EscrowNotFunded,
+    #[msg("Signer is not the platform release authority")]
+    UnauthorizedReleaseAuthority,
+    #[msg("Reservation does not match the escrow")]
+    InvalidReservation,
+    #[msg("Invalid mint provided")]
+    InvalidMint,
+    #[msg("Platform treasury does not match the platform config")]
+    InvalidTreasury,
+}
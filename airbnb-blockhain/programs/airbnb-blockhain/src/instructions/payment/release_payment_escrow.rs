@@ -1,84 +1,200 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_2022::{transfer_checked, TransferChecked, Token2022},
+    token_2022::Token2022,
     token_interface::{Mint, TokenAccount},
 };
 
+use crate::instructions::token::{transfer_from_escrow_vault, transfer_net_from_escrow_vault};
+use crate::events::*;
+use crate::math;
 use crate::states::*;
 
 #[inline(never)]
 pub fn _release_payment_escrow(
     ctx: Context<ReleasePaymentEscrow>,
 ) -> Result<()> {
+    let decimals = ctx.accounts.platform_config.mint_decimals;
+
+    let settlement = pay_host_from_escrow(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.host_token_account,
+        &ctx.accounts.release_payment_escrow,
+        &ctx.accounts.reservation,
+        &mut ctx.accounts.host,
+        &ctx.accounts.mint,
+        decimals,
+    )?;
+    
+    // Sweep whatever is left in the vault (the platform fee) to the treasury
+    let platform_fee_swept = settlement.remaining;
+    transfer_from_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.platform_treasury,
+        &ctx.accounts.release_payment_escrow,
+        &ctx.accounts.mint,
+        platform_fee_swept,
+        decimals,
+    )?;
+    
+    // Update escrow status
     let release_payment_escrow = &mut ctx.accounts.release_payment_escrow;
+    release_payment_escrow.set_status(EscrowStatus::Released)?;
+    ctx.accounts.platform_config.escrow_settled()?;
+    
+    msg!("Payment escrow released:");
+    msg!("Platform fee swept to treasury: {}", platform_fee_swept);
+    msg!("Host: {}", release_payment_escrow.host);
+
+    emit_cpi!(EscrowReleased {
+        payment_escrow: release_payment_escrow.key(),
+        reservation: release_payment_escrow.reservation,
+        host: release_payment_escrow.host,
+        host_amount: settlement.host_amount,
+        platform_fee: platform_fee_swept,
+        penalty_withheld: settlement.penalty_withheld,
+    });
     
+    Ok(())
+}
+
+/// Outcome of paying the host out of an escrow vault
+pub struct ReleaseSettlement {
+    pub host_amount: u64,      // Sent to the host, grossed up for transfer fees
+    pub penalty_withheld: u64, // Host cancellation penalty kept back
+    pub remaining: u64,        // Still in the vault, belongs to the platform
+}
+
+/// Check the release conditions and pay the host their share of the escrow
+/// Shared by the operator release and the permissionless crank, the caller settles the rest
+#[allow(clippy::too_many_arguments)]
+pub fn pay_host_from_escrow<'info>(
+    token_program: &Program<'info, Token2022>,
+    escrow_vault: &InterfaceAccount<'info, TokenAccount>,
+    host_token_account: &InterfaceAccount<'info, TokenAccount>,
+    payment_escrow: &Account<'info, PaymentEscrow>,
+    reservation: &Reservation,
+    host: &mut Host,
+    mint: &InterfaceAccount<'info, Mint>,
+    decimals: u8,
+) -> Result<ReleaseSettlement> {
     // Verify release conditions
     require!(
-        release_payment_escrow.status == EscrowStatus::Funded,
+        payment_escrow.status != EscrowStatus::Disputed,
+        ReleasePaymentEscrowError::EscrowDisputed
+    );
+    require!(
+        payment_escrow.status == EscrowStatus::Funded,
         ReleasePaymentEscrowError::EscrowNotFunded
     );
     
+    // The guest must have checked in, funds are never released for a stay that did not start
+    require!(
+        matches!(
+            reservation.status,
+            ReservationStatus::CheckedIn | ReservationStatus::Completed
+        ),
+        ReleasePaymentEscrowError::StayNotStarted
+    );
+
     // Check if release date has passed (optional - could be manual release too)
     let current_time = Clock::get()?.unix_timestamp as u64;
     require!(
-        current_time >= release_payment_escrow.release_date,
+        current_time >= payment_escrow.release_date,
         ReleasePaymentEscrowError::ReleaseNotYetAllowed
     );
     
-    let host_net_amount = release_payment_escrow.amount - release_payment_escrow.platform_fee;
-    
-    // Account for Token2022 transfer fee (5% = 500 basis points)
-    // To ensure host receives the intended amount, we need to gross up the transfer
-    // If transfer fee is 5%, and we want host to receive X, we need to send X / (1 - 0.05) = X / 0.95
-    let transfer_amount = (host_net_amount * 10000) / 9500; // Gross up by ~5.26% to offset 5% fee
+    let host_net_amount = math::checked_sub(payment_escrow.amount, payment_escrow.platform_fee)?;
+
+    // Outstanding host cancellation penalties stay in the vault and go to the treasury
+    let penalty_withheld = host.withhold_penalty(host_net_amount);
+    let host_net_amount = host_net_amount - penalty_withheld;
     
-    // Transfer host payment from platform treasury to host
-    transfer_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            TransferChecked {
-                from: ctx.accounts.platform_treasury.to_account_info(),
-                to: ctx.accounts.host_token_account.to_account_info(),
-                authority: ctx.accounts.platform_authority.to_account_info(),
-                mint: ctx.accounts.mint.to_account_info(),
-            },
-        ),
-        transfer_amount,
-        9, // Token decimals
+    // Gross up by the mint's TransferFeeConfig (current epoch, max fee cap) so the
+    // host receives exactly the net amount; the transfer fee comes out of the platform fee
+    let vault_balance = escrow_vault.amount;
+    let host_amount = transfer_net_from_escrow_vault(
+        token_program,
+        escrow_vault,
+        host_token_account,
+        payment_escrow,
+        mint,
+        host_net_amount,
+        vault_balance,
+        decimals,
     )?;
-    
-    // Update escrow status
-    release_payment_escrow.status = EscrowStatus::Released;
-    
-    msg!("Payment escrow released:");
+
     msg!("Host net amount (after platform fee): {}", host_net_amount);
-    msg!("Transfer amount (grossed up for fees): {}", transfer_amount);
-    msg!("Platform fee retained: {}", release_payment_escrow.platform_fee);
-    msg!("Host: {}", release_payment_escrow.host);
-    
-    Ok(())
+    msg!("Transfer amount (grossed up for fees): {}", host_amount);
+    msg!("Penalty withheld: {}", penalty_withheld);
+
+    Ok(ReleaseSettlement {
+        host_amount,
+        penalty_withheld,
+        remaining: math::checked_sub(vault_balance, host_amount)?,
+    })
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ReleasePaymentEscrow<'info> {
     /// Platform authority (can release payments)
-    #[account(mut)]
+    #[account(
+        mut,
+        address = platform_config.release_authority @ ReleasePaymentEscrowError::UnauthorizedReleaseAuthority
+    )]
     pub platform_authority: Signer<'info>,
     
+    /// Global platform configuration (mint, treasury, release authority)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    
     /// The escrow account to release
     #[account(
         mut,
+        constraint = release_payment_escrow.status != EscrowStatus::Disputed @ ReleasePaymentEscrowError::EscrowDisputed,
         constraint = release_payment_escrow.status == EscrowStatus::Funded @ ReleasePaymentEscrowError::EscrowNotFunded
     )]
     pub release_payment_escrow: Account<'info, PaymentEscrow>,
     
+    /// The reservation this escrow is for
+    #[account(
+        address = release_payment_escrow.reservation @ ReleasePaymentEscrowError::InvalidReservation
+    )]
+    pub reservation: Box<Account<'info, Reservation>>,
+
+    /// Host profile of the payee, outstanding cancellation penalties are withheld
+    #[account(
+        mut,
+        seeds = [HOST_SEED.as_bytes(), release_payment_escrow.host.as_ref()],
+        bump = host.bump,
+    )]
+    pub host: Box<Account<'info, Host>>,
+
+    /// Vault holding the escrowed funds (source of payment)
+    #[account(
+        mut,
+        seeds = [ESCROW_VAULT_SEED.as_bytes(), release_payment_escrow.key().as_ref()],
+        bump = release_payment_escrow.vault_bump,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    
     /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ ReleasePaymentEscrowError::InvalidMint
+    )]
     pub mint: InterfaceAccount<'info, Mint>,
     
-    /// Platform treasury account (source of payment)
+    /// Platform treasury account (receives the platform fee)
     #[account(
         mut,
+        address = platform_config.treasury @ ReleasePaymentEscrowError::InvalidTreasury,
         constraint = platform_treasury.mint == mint.key() @ ReleasePaymentEscrowError::InvalidTreasuryMint
     )]
     pub platform_treasury: InterfaceAccount<'info, TokenAccount>,
@@ -105,4 +221,16 @@ pub enum ReleasePaymentEscrowError {
     ReleaseNotYetAllowed,
     #[msg("Platform treasury mint does not match payment mint")]
     InvalidTreasuryMint,
+    #[msg("Signer is not the platform release authority")]
+    UnauthorizedReleaseAuthority,
+    #[msg("Invalid mint provided")]
+    InvalidMint,
+    #[msg("Platform treasury does not match the platform config")]
+    InvalidTreasury,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Reservation does not match the escrow")]
+    InvalidReservation,
+    #[msg("Reservation must be checked in or completed before release")]
+    StayNotStarted,
 }
@@ -0,0 +1,216 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::token::{transfer_from_escrow_vault, transfer_net_from_escrow_vault};
+use crate::events::*;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _resolve_dispute(
+    ctx: Context<ResolveDispute>,
+    guest_share_bps: u16,
+) -> Result<()> {
+    require!(
+        guest_share_bps as u64 <= BPS_DENOMINATOR,
+        ResolveDisputeError::InvalidGuestShare
+    );
+
+    let payment_escrow = &ctx.accounts.payment_escrow;
+    let decimals = ctx.accounts.platform_config.mint_decimals;
+
+    // Split the host amount between guest and host, the platform fee is kept
+    let distributable = math::checked_sub(payment_escrow.amount, payment_escrow.platform_fee)?;
+    let guest_net_amount = math::bps_of(distributable, guest_share_bps)?;
+    let host_net_amount = math::checked_sub(distributable, guest_net_amount)?;
+
+    // Outstanding host cancellation penalties stay in the vault and go to the treasury
+    let penalty_withheld = ctx.accounts.host.withhold_penalty(host_net_amount);
+    let host_net_amount = host_net_amount - penalty_withheld;
+
+    // Both sides receive exactly their share, the transfer fees come out of the platform fee
+    // Fails rather than underpay if the vault cannot cover the fees
+    let vault_balance = ctx.accounts.escrow_vault.amount;
+    let guest_amount = transfer_net_from_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.guest_token_account,
+        payment_escrow,
+        &ctx.accounts.mint,
+        guest_net_amount,
+        vault_balance,
+        decimals,
+    )?;
+    let host_amount = transfer_net_from_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.host_token_account,
+        payment_escrow,
+        &ctx.accounts.mint,
+        host_net_amount,
+        math::checked_sub(vault_balance, guest_amount)?,
+        decimals,
+    )?;
+
+    // Sweep whatever is left in the vault (the platform fee) to the treasury
+    let platform_fee_swept = math::checked_sub(vault_balance, math::checked_add(guest_amount, host_amount)?)?;
+    transfer_from_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.platform_treasury,
+        payment_escrow,
+        &ctx.accounts.mint,
+        platform_fee_swept,
+        decimals,
+    )?;
+
+    // A dispute fully in the guest's favour is a refund
+    let fully_refunded = guest_share_bps as u64 == BPS_DENOMINATOR;
+    ctx.accounts.payment_escrow.set_status(if fully_refunded {
+        EscrowStatus::Refunded
+    } else {
+        EscrowStatus::Released
+    })?;
+    ctx.accounts.platform_config.escrow_settled()?;
+    if fully_refunded {
+        ctx.accounts.reservation.set_payment_status(PaymentStatus::Refunded)?;
+    }
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.status = DisputeStatus::Resolved;
+    dispute.resolved_at = Clock::get()?.unix_timestamp as u64;
+    dispute.guest_share_bps = guest_share_bps;
+
+    msg!("Dispute resolved:");
+    msg!("Guest amount: {}", guest_amount);
+    msg!("Host amount: {}", host_amount);
+    msg!("Penalty withheld: {}", penalty_withheld);
+    msg!("Platform fee swept to treasury: {}", platform_fee_swept);
+
+    emit_cpi!(DisputeResolved {
+        dispute: dispute.key(),
+        payment_escrow: dispute.payment_escrow,
+        guest_share_bps,
+        guest_amount,
+        host_amount,
+        platform_fee: platform_fee_swept,
+        penalty_withheld,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    /// Arbiter configured on the platform
+    #[account(
+        mut,
+        address = platform_config.arbiter @ ResolveDisputeError::UnauthorizedArbiter
+    )]
+    pub arbiter: Signer<'info>,
+
+    /// Global platform configuration (mint, treasury, arbiter)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// The dispute being resolved
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump = dispute.bump,
+        constraint = dispute.status == DisputeStatus::Open @ ResolveDisputeError::DisputeNotOpen
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    /// The disputed escrow
+    #[account(
+        mut,
+        constraint = payment_escrow.status == EscrowStatus::Disputed @ ResolveDisputeError::EscrowNotDisputed
+    )]
+    pub payment_escrow: Box<Account<'info, PaymentEscrow>>,
+
+    /// The reservation this escrow is for
+    #[account(
+        mut,
+        address = payment_escrow.reservation @ ResolveDisputeError::InvalidReservation
+    )]
+    pub reservation: Box<Account<'info, Reservation>>,
+
+    /// Host profile of the payee, outstanding cancellation penalties are withheld
+    #[account(
+        mut,
+        seeds = [HOST_SEED.as_bytes(), payment_escrow.host.as_ref()],
+        bump = host.bump,
+    )]
+    pub host: Box<Account<'info, Host>>,
+
+    /// Vault holding the escrowed funds
+    #[account(
+        mut,
+        seeds = [ESCROW_VAULT_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump = payment_escrow.vault_bump,
+    )]
+    pub escrow_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ ResolveDisputeError::InvalidMint
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Platform treasury account (receives the platform fee)
+    #[account(
+        mut,
+        address = platform_config.treasury @ ResolveDisputeError::InvalidTreasury
+    )]
+    pub platform_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Guest's token account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payment_escrow.guest,
+        associated_token::token_program = token_program
+    )]
+    pub guest_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Host's token account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payment_escrow.host,
+        associated_token::token_program = token_program
+    )]
+    pub host_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum ResolveDisputeError {
+    #[msg("Signer is not the platform arbiter")]
+    UnauthorizedArbiter,
+    #[msg("Guest share basis points cannot exceed 10000")]
+    InvalidGuestShare,
+    #[msg("Dispute is not open")]
+    DisputeNotOpen,
+    #[msg("Escrow is not under dispute")]
+    EscrowNotDisputed,
+    #[msg("Reservation does not match the escrow")]
+    InvalidReservation,
+    #[msg("Invalid mint provided")]
+    InvalidMint,
+    #[msg("Platform treasury does not match the platform config")]
+    InvalidTreasury,
+}
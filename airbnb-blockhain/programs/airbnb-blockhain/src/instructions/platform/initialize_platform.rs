@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _initialize_platform(
+    ctx: Context<InitializePlatform>,
+    fee_bps: u16,
+    release_authority: Pubkey,
+    arbiter: Pubkey,
+    refund_fee_policy: RefundFeePolicy,
+    host_cancel_penalty_bps: u16,
+    crank_bounty_bps: u16,
+) -> Result<()> {
+    require!(
+        fee_bps as u64 <= BPS_DENOMINATOR,
+        InitializePlatformError::InvalidFeeBps
+    );
+    require!(
+        host_cancel_penalty_bps as u64 <= BPS_DENOMINATOR,
+        InitializePlatformError::InvalidPenaltyBps
+    );
+    require!(
+        crank_bounty_bps as u64 <= BPS_DENOMINATOR,
+        InitializePlatformError::InvalidBountyBps
+    );
+
+    let platform_config = &mut ctx.accounts.platform_config;
+
+    platform_config.admin = ctx.accounts.admin.key();
+    platform_config.fee_bps = fee_bps;
+    platform_config.accepted_mint = ctx.accounts.mint.key();
+    platform_config.mint_decimals = ctx.accounts.mint.decimals;
+    platform_config.treasury = ctx.accounts.platform_treasury.key();
+    platform_config.release_authority = release_authority;
+    platform_config.arbiter = arbiter;
+    platform_config.refund_fee_policy = refund_fee_policy;
+    platform_config.host_cancel_penalty_bps = host_cancel_penalty_bps;
+    platform_config.crank_bounty_bps = crank_bounty_bps;
+    platform_config.open_escrow_count = 0;
+    platform_config.updated_at = Clock::get()?.unix_timestamp as u64;
+    platform_config.bump = ctx.bumps.platform_config;
+
+    msg!("Platform initialized:");
+    msg!("Fee bps: {}", fee_bps);
+    msg!("Accepted mint: {}", platform_config.accepted_mint);
+    msg!("Treasury: {}", platform_config.treasury);
+
+    emit_cpi!(PlatformInitialized {
+        platform_config: platform_config.key(),
+        admin: platform_config.admin,
+        fee_bps,
+        accepted_mint: platform_config.accepted_mint,
+        treasury: platform_config.treasury,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializePlatform<'info> {
+    /// Must be the program's upgrade authority, so the deployer cannot be front-run
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Program data account of this program, holds the upgrade authority
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = ProgramData::owner(),
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ InitializePlatformError::UnauthorizedAdmin
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// Singleton platform configuration
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PlatformConfig::INIT_SPACE,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// The only mint accepted for payments
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token account that collects platform fees
+    #[account(
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub platform_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum InitializePlatformError {
+    #[msg("Fee basis points cannot exceed 10000")]
+    InvalidFeeBps,
+    #[msg("Host cancellation penalty cannot exceed 10000 basis points")]
+    InvalidPenaltyBps,
+    #[msg("Crank bounty cannot exceed 10000 basis points")]
+    InvalidBountyBps,
+    #[msg("Only the program upgrade authority can initialize the platform")]
+    UnauthorizedAdmin,
+}
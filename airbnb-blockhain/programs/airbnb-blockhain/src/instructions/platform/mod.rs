@@ -0,0 +1,5 @@
+pub mod initialize_platform;
+pub mod update_platform_config;
+
+pub use initialize_platform::*;
+pub use update_platform_config::*;
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+pub fn _update_platform_config(
+    ctx: Context<UpdatePlatformConfig>,
+    fee_bps: Option<u16>,
+    release_authority: Option<Pubkey>,
+    arbiter: Option<Pubkey>,
+    new_admin: Option<Pubkey>,
+    refund_fee_policy: Option<RefundFeePolicy>,
+    host_cancel_penalty_bps: Option<u16>,
+    crank_bounty_bps: Option<u16>,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+
+    if let Some(fee_bps) = fee_bps {
+        require!(
+            fee_bps as u64 <= BPS_DENOMINATOR,
+            UpdatePlatformConfigError::InvalidFeeBps
+        );
+        platform_config.fee_bps = fee_bps;
+    }
+
+    if let Some(release_authority) = release_authority {
+        platform_config.release_authority = release_authority;
+    }
+
+    if let Some(arbiter) = arbiter {
+        platform_config.arbiter = arbiter;
+    }
+
+    if let Some(refund_fee_policy) = refund_fee_policy {
+        platform_config.refund_fee_policy = refund_fee_policy;
+    }
+
+    if let Some(host_cancel_penalty_bps) = host_cancel_penalty_bps {
+        require!(
+            host_cancel_penalty_bps as u64 <= BPS_DENOMINATOR,
+            UpdatePlatformConfigError::InvalidPenaltyBps
+        );
+        platform_config.host_cancel_penalty_bps = host_cancel_penalty_bps;
+    }
+
+    if let Some(crank_bounty_bps) = crank_bounty_bps {
+        require!(
+            crank_bounty_bps as u64 <= BPS_DENOMINATOR,
+            UpdatePlatformConfigError::InvalidBountyBps
+        );
+        platform_config.crank_bounty_bps = crank_bounty_bps;
+    }
+
+    if let Some(new_admin) = new_admin {
+        platform_config.admin = new_admin;
+    }
+
+    // Switching the accepted mint requires a treasury for that mint, and no escrow
+    // left holding the old one since payouts only accept the configured mint
+    if let Some(mint) = &ctx.accounts.mint {
+        require!(
+            ctx.accounts.platform_treasury.is_some(),
+            UpdatePlatformConfigError::MissingTreasury
+        );
+        require!(
+            mint.key() == platform_config.accepted_mint || platform_config.open_escrow_count == 0,
+            UpdatePlatformConfigError::EscrowsOutstanding
+        );
+        platform_config.accepted_mint = mint.key();
+        platform_config.mint_decimals = mint.decimals;
+    }
+
+    if let Some(platform_treasury) = &ctx.accounts.platform_treasury {
+        require!(
+            platform_treasury.mint == platform_config.accepted_mint,
+            UpdatePlatformConfigError::InvalidTreasuryMint
+        );
+        platform_config.treasury = platform_treasury.key();
+    }
+
+    platform_config.updated_at = Clock::get()?.unix_timestamp as u64;
+
+    msg!("Platform config updated:");
+    msg!("Fee bps: {}", platform_config.fee_bps);
+    msg!("Accepted mint: {}", platform_config.accepted_mint);
+    msg!("Treasury: {}", platform_config.treasury);
+    msg!("Release authority: {}", platform_config.release_authority);
+    msg!("Arbiter: {}", platform_config.arbiter);
+    msg!("Host cancel penalty bps: {}", platform_config.host_cancel_penalty_bps);
+    msg!("Crank bounty bps: {}", platform_config.crank_bounty_bps);
+
+    emit_cpi!(PlatformConfigUpdated {
+        platform_config: platform_config.key(),
+        admin: platform_config.admin,
+        fee_bps: platform_config.fee_bps,
+        accepted_mint: platform_config.accepted_mint,
+        treasury: platform_config.treasury,
+        release_authority: platform_config.release_authority,
+        arbiter: platform_config.arbiter,
+        host_cancel_penalty_bps: platform_config.host_cancel_penalty_bps,
+        crank_bounty_bps: platform_config.crank_bounty_bps,
+        updated_at: platform_config.updated_at,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdatePlatformConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+        has_one = admin @ UpdatePlatformConfigError::UnauthorizedAdmin,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// New accepted mint (optional)
+    #[account(mint::token_program = token_program)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// New treasury token account (optional)
+    #[account(token::token_program = token_program)]
+    pub platform_treasury: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[error_code]
+pub enum UpdatePlatformConfigError {
+    #[msg("Only the platform admin can update the config")]
+    UnauthorizedAdmin,
+    #[msg("Fee basis points cannot exceed 10000")]
+    InvalidFeeBps,
+    #[msg("Host cancellation penalty cannot exceed 10000 basis points")]
+    InvalidPenaltyBps,
+    #[msg("Crank bounty cannot exceed 10000 basis points")]
+    InvalidBountyBps,
+    #[msg("A treasury for the new mint must be provided")]
+    MissingTreasury,
+    #[msg("Platform treasury mint does not match payment mint")]
+    InvalidTreasuryMint,
+    #[msg("The mint cannot change while escrows still hold funds")]
+    EscrowsOutstanding,
+}
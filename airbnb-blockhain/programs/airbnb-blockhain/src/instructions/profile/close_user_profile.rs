@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _close_user_profile(ctx: Context<CloseUserProfile>) -> Result<()> {
+    msg!("User profile closed: {}", ctx.accounts.user_profile.authority);
+
+    emit_cpi!(UserProfileClosed {
+        user_profile: ctx.accounts.user_profile.key(),
+        authority: ctx.accounts.user_profile.authority,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseUserProfile<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [USER_PROFILE_SEED.as_bytes(), authority.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.roles == 0 @ CloseUserProfileError::ProfileHasRoles
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+}
+
+#[error_code]
+pub enum CloseUserProfileError {
+    #[msg("Profile still has host or guest accounts, close them first")]
+    ProfileHasRoles,
+}
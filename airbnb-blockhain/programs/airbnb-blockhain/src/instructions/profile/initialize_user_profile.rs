@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+use crate::validation;
+
+#[inline(never)]
+pub fn _initialize_user_profile(
+    ctx: Context<InitializeUserProfile>,
+    name: String,
+    image_url: String,
+    profile_commitment: [u8; 32],
+    metadata_uri: String,
+    preferred_language: String,
+) -> Result<()> {
+    validation::profile_name(&name)?;
+    validation::profile_image_url(&image_url)?;
+    validation::metadata_uri(&metadata_uri)?;
+    validation::preferred_language(&preferred_language)?;
+
+    let created_at = Clock::get()?.unix_timestamp as u64;
+    let user_profile = &mut ctx.accounts.user_profile;
+
+    user_profile.authority = ctx.accounts.authority.key();
+    user_profile.name = name;
+    user_profile.image_url = image_url;
+    user_profile.profile_commitment = profile_commitment;
+    user_profile.metadata_uri = metadata_uri;
+    user_profile.preferred_language = preferred_language;
+    // Roles are granted by creating their extension accounts
+    user_profile.roles = 0;
+    user_profile.created_at = created_at;
+    user_profile.updated_at = created_at;
+    user_profile.bump = ctx.bumps.user_profile;
+
+    emit_cpi!(UserProfileCreated {
+        user_profile: user_profile.key(),
+        authority: user_profile.authority,
+        created_at,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeUserProfile<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UserProfile::INIT_SPACE,
+        seeds = [USER_PROFILE_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    pub system_program: Program<'info, System>,
+}
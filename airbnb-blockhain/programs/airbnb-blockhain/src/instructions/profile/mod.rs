@@ -0,0 +1,7 @@
+pub mod close_user_profile;
+pub mod initialize_user_profile;
+pub mod update_user_profile;
+
+pub use close_user_profile::*;
+pub use initialize_user_profile::*;
+pub use update_user_profile::*;
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ProfileError;
+use crate::events::*;
+use crate::states::*;
+use crate::validation;
+
+#[inline(never)]
+pub fn _update_user_profile(
+    ctx: Context<UpdateUserProfile>,
+    name: Option<String>,
+    image_url: Option<String>,
+    profile_commitment: Option<[u8; 32]>,
+    metadata_uri: Option<String>,
+    preferred_language: Option<String>,
+) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+
+    // Everything is checked before anything is written
+    if let Some(name) = &name {
+        validation::profile_name(name)?;
+    }
+    if let Some(image_url) = &image_url {
+        validation::profile_image_url(image_url)?;
+    }
+    if let Some(metadata_uri) = &metadata_uri {
+        validation::metadata_uri(metadata_uri)?;
+    }
+    if let Some(preferred_language) = &preferred_language {
+        validation::preferred_language(preferred_language)?;
+    }
+
+    if let Some(name) = name {
+        user_profile.name = name;
+    }
+    if let Some(image_url) = image_url {
+        user_profile.image_url = image_url;
+    }
+    if let Some(profile_commitment) = profile_commitment {
+        user_profile.profile_commitment = profile_commitment;
+    }
+    if let Some(metadata_uri) = metadata_uri {
+        user_profile.metadata_uri = metadata_uri;
+    }
+    if let Some(preferred_language) = preferred_language {
+        user_profile.preferred_language = preferred_language;
+    }
+    user_profile.updated_at = Clock::get()?.unix_timestamp as u64;
+
+    msg!("User profile updated: {}", user_profile.authority);
+
+    emit_cpi!(UserProfileUpdated {
+        user_profile: user_profile.key(),
+        authority: user_profile.authority,
+        updated_at: user_profile.updated_at,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateUserProfile<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED.as_bytes(), authority.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == authority.key() @ ProfileError::UnauthorizedUser
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+}
@@ -0,0 +1,218 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::initialize_reservation::create_reservation;
+use crate::instructions::payment::{fill_payment_escrow, InitializePaymentEscrowError};
+use crate::instructions::token::transfer_to_escrow_vault;
+use crate::errors::GuestError;
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _book_listing(
+    ctx: Context<BookListing>,
+    escrow_id: u64,
+    start_date: u64,
+    end_date: u64,
+    guest_count: u8,
+    release_date: u64,
+) -> Result<()> {
+    let guest = ctx.accounts.guest_authority.key();
+
+    // Reserve the dates, then pay for them, any failure rolls back both
+    create_reservation(
+        &mut ctx.accounts.reservation,
+        &mut ctx.accounts.booking_index,
+        &mut ctx.accounts.guest,
+        &mut ctx.accounts.listing,
+        &ctx.accounts.host,
+        &ctx.accounts.availability,
+        start_date,
+        end_date,
+        guest_count,
+        ctx.bumps.reservation,
+        ctx.bumps.booking_index,
+    )?;
+
+    // The guest pays the price the reservation was just quoted
+    let amount = ctx.accounts.reservation.total_price;
+    let platform_fee = fill_payment_escrow(
+        &mut ctx.accounts.payment_escrow,
+        &mut ctx.accounts.reservation,
+        &mut ctx.accounts.platform_config,
+        guest,
+        escrow_id,
+        amount,
+        release_date,
+        ctx.bumps.payment_escrow,
+        ctx.bumps.escrow_vault,
+    )?;
+    let platform_config = &ctx.accounts.platform_config;
+
+    // Transfer tokens from guest to the escrow vault (held until release or refund)
+    transfer_to_escrow_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.guest_token_account,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.guest_authority,
+        &ctx.accounts.mint,
+        amount,
+        platform_config.mint_decimals,
+    )?;
+
+    msg!("Listing booked:");
+    msg!("Amount: {}", amount);
+    msg!("Platform fee: {} ({} bps)", platform_fee, platform_config.fee_bps);
+    msg!("Release date: {}", release_date);
+
+    let reservation = &ctx.accounts.reservation;
+    emit_cpi!(ReservationCreated {
+        reservation: reservation.key(),
+        listing: reservation.listing,
+        guest,
+        host: reservation.host,
+        start_date,
+        end_date,
+        guest_count,
+        total_nights: reservation.total_nights,
+        price_per_night: reservation.price_per_night,
+        total_price: reservation.total_price,
+    });
+    emit_cpi!(EscrowFunded {
+        payment_escrow: ctx.accounts.payment_escrow.key(),
+        reservation: reservation.key(),
+        guest,
+        host: reservation.host,
+        amount,
+        platform_fee,
+        release_date,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(escrow_id: u64)]
+pub struct BookListing<'info> {
+    #[account(mut)]
+    pub guest_authority: Signer<'info>,
+
+    /// Profile of the signer, must hold the guest role
+    #[account(
+        seeds = [USER_PROFILE_SEED.as_bytes(), guest_authority.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.has_role(ROLE_GUEST) @ GuestError::MissingGuestRole
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+
+    /// Guest role of the signer, its reservation count seeds the reservation PDA
+    #[account(
+        mut,
+        seeds = [GUEST_SEED.as_bytes(), guest_authority.key().as_ref()],
+        bump = guest.bump,
+        constraint = guest.profile == user_profile.key() @ GuestError::ProfileMismatch
+    )]
+    pub guest: Box<Account<'info, Guest>>,
+
+    /// The listing being booked
+    #[account(mut)]
+    pub listing: Box<Account<'info, Listing>>,
+
+    /// Host profile of the listing owner
+    #[account(address = listing.host)]
+    pub host: Box<Account<'info, Host>>,
+
+    /// Calendar of the listing being booked
+    #[account(
+        mut,
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump = availability.load()?.bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+
+    /// The reservation to be created
+    #[account(
+        init,
+        payer = guest_authority,
+        space = 8 + Reservation::INIT_SPACE,
+        seeds = [
+            RESERVATION_SEED.as_bytes(),
+            guest_authority.key().as_ref(),
+            &guest.reservation_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub reservation: Box<Account<'info, Reservation>>,
+
+    /// Next entry in the listing's booking index
+    #[account(
+        init,
+        payer = guest_authority,
+        space = 8 + BookingIndex::INIT_SPACE,
+        seeds = [
+            BOOKING_INDEX_SEED.as_bytes(),
+            listing.key().as_ref(),
+            &listing.booking_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub booking_index: Box<Account<'info, BookingIndex>>,
+
+    /// The escrow account to be created
+    #[account(
+        init,
+        payer = guest_authority,
+        space = 8 + PaymentEscrow::INIT_SPACE,
+        seeds = [
+            PAYMENT_ESCROW_SEED.as_bytes(),
+            reservation.key().as_ref(),
+            &escrow_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub payment_escrow: Box<Account<'info, PaymentEscrow>>,
+
+    /// Global platform configuration (fee, mint)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ InitializePaymentEscrowError::InvalidMint
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Guest's token account (source of payment)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = guest_authority,
+        associated_token::token_program = token_program
+    )]
+    pub guest_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Vault holding this booking's funds, owned by the escrow PDA
+    #[account(
+        init,
+        payer = guest_authority,
+        seeds = [ESCROW_VAULT_SEED.as_bytes(), payment_escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = payment_escrow,
+        token::token_program = token_program,
+    )]
+    pub escrow_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,277 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::token::{transfer_from_escrow_vault, transfer_net_from_escrow_vault};
+use crate::events::*;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _cancel_reservation(ctx: Context<CancelReservation>) -> Result<()> {
+    let reservation_key = ctx.accounts.reservation.key();
+    let now = Clock::get()?.unix_timestamp as u64;
+
+    // A linked escrow must be passed in so it cannot be left funded
+    if let Some(linked_escrow) = ctx.accounts.reservation.payment_escrow {
+        require!(
+            ctx.accounts
+                .payment_escrow
+                .as_ref()
+                .is_some_and(|payment_escrow| payment_escrow.key() == linked_escrow),
+            CancelReservationError::MissingEscrowAccounts
+        );
+    }
+
+    // The policy agreed at booking only applies once the host has confirmed the stay
+    let refund_bps = match ctx.accounts.reservation.status {
+        ReservationStatus::Pending => BPS_DENOMINATOR as u16,
+        _ => ctx
+            .accounts
+            .reservation
+            .cancellation_policy
+            .refund_bps(now, ctx.accounts.reservation.start_date),
+    };
+
+    let mut refund_amount = 0;
+    let mut host_amount = 0;
+    let mut platform_fee_swept = 0;
+    let mut penalty_withheld = 0;
+    if let Some(payment_escrow) = &mut ctx.accounts.payment_escrow {
+        require!(
+            payment_escrow.reservation == reservation_key,
+            CancelReservationError::InvalidEscrow
+        );
+        require!(
+            payment_escrow.status != EscrowStatus::Disputed,
+            CancelReservationError::EscrowDisputed
+        );
+
+        if payment_escrow.status == EscrowStatus::Funded {
+            let (
+                Some(escrow_vault),
+                Some(mint),
+                Some(guest_token_account),
+                Some(host_token_account),
+                Some(platform_treasury),
+            ) = (
+                &ctx.accounts.escrow_vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.guest_token_account,
+                &ctx.accounts.host_token_account,
+                &ctx.accounts.platform_treasury,
+            ) else {
+                return err!(CancelReservationError::MissingEscrowAccounts);
+            };
+            require_keys_eq!(
+                escrow_vault.key(),
+                payment_escrow.vault_address(&payment_escrow.key())?,
+                CancelReservationError::InvalidEscrowVault
+            );
+            let platform_config = &ctx.accounts.platform_config;
+            let decimals = platform_config.mint_decimals;
+
+            // The policy share applies to the stay, the fee follows the platform refund policy
+            let stay_amount = math::checked_sub(payment_escrow.amount, payment_escrow.platform_fee)?;
+            let stay_refund = math::bps_of(stay_amount, refund_bps)?;
+            let fee_refund = match platform_config.refund_fee_policy {
+                RefundFeePolicy::RetainPlatformFee => 0,
+                RefundFeePolicy::RefundPlatformFee => {
+                    math::bps_of(payment_escrow.platform_fee, refund_bps)?
+                }
+            };
+
+            // Never send more than the vault holds
+            let vault_balance = escrow_vault.amount;
+            refund_amount = math::checked_add(stay_refund, fee_refund)?.min(vault_balance);
+            transfer_from_escrow_vault(
+                &ctx.accounts.token_program,
+                escrow_vault,
+                guest_token_account,
+                payment_escrow,
+                mint,
+                refund_amount,
+                decimals,
+            )?;
+
+            // Outstanding host cancellation penalties stay in the vault and go to the treasury
+            let host_share = math::checked_sub(stay_amount, stay_refund)?;
+            penalty_withheld = ctx.accounts.host.withhold_penalty(host_share);
+
+            // The host nets the rest of the stay, the transfer fee comes out of the platform fee
+            host_amount = transfer_net_from_escrow_vault(
+                &ctx.accounts.token_program,
+                escrow_vault,
+                host_token_account,
+                payment_escrow,
+                mint,
+                host_share - penalty_withheld,
+                math::checked_sub(vault_balance, refund_amount)?,
+                decimals,
+            )?;
+
+            // Sweep whatever is left in the vault (the platform fee) to the treasury
+            platform_fee_swept = math::checked_sub(
+                vault_balance,
+                math::checked_add(refund_amount, host_amount)?,
+            )?;
+            transfer_from_escrow_vault(
+                &ctx.accounts.token_program,
+                escrow_vault,
+                platform_treasury,
+                payment_escrow,
+                mint,
+                platform_fee_swept,
+                decimals,
+            )?;
+
+            // A cancellation with a full refund is a refund, anything else pays the host
+            let fully_refunded = refund_bps as u64 == BPS_DENOMINATOR;
+            payment_escrow.set_status(if fully_refunded {
+                EscrowStatus::Refunded
+            } else {
+                EscrowStatus::Released
+            })?;
+            ctx.accounts.platform_config.escrow_settled()?;
+            if fully_refunded {
+                ctx.accounts.reservation.set_payment_status(PaymentStatus::Refunded)?;
+            }
+        }
+    }
+
+    let reservation = &mut ctx.accounts.reservation;
+    reservation.transition_to(ReservationStatus::Cancelled)?;
+
+    // Give the nights back to the calendar
+    ctx.accounts.availability.load_mut()?.release(
+        reservation.start_date / SECONDS_PER_DAY,
+        reservation.end_date / SECONDS_PER_DAY,
+    );
+
+    msg!("Reservation cancelled:");
+    msg!("Refund: {} ({} bps)", refund_amount, refund_bps);
+    msg!("Host amount: {}", host_amount);
+    msg!("Penalty withheld: {}", penalty_withheld);
+    msg!("Platform fee swept to treasury: {}", platform_fee_swept);
+
+    emit_cpi!(ReservationCancelled {
+        reservation: reservation.key(),
+        listing: reservation.listing,
+        guest: reservation.guest,
+        refund_bps,
+        refund_amount,
+        host_amount,
+        platform_fee: platform_fee_swept,
+        penalty_withheld,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelReservation<'info> {
+    pub guest_authority: Signer<'info>,
+
+    /// The listing being booked
+    #[account(
+        address = reservation.listing @ CancelReservationError::InvalidListing
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    /// Host profile of the listing owner, outstanding cancellation penalties are withheld
+    #[account(
+        mut,
+        address = listing.host @ CancelReservationError::InvalidHost
+    )]
+    pub host: Box<Account<'info, Host>>,
+
+    #[account(
+        mut,
+        constraint = reservation.guest == guest_authority.key() @ CancelReservationError::UnauthorizedGuest
+    )]
+    pub reservation: Box<Account<'info, Reservation>>,
+
+    /// Calendar of the listing
+    #[account(
+        mut,
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump = availability.load()?.bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+
+    /// Global platform configuration (mint, treasury, refund fee policy)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// Escrow funded for this reservation, if any
+    #[account(mut)]
+    pub payment_escrow: Option<Box<Account<'info, PaymentEscrow>>>,
+
+    /// Vault holding the escrowed funds
+    #[account(mut)]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ CancelReservationError::InvalidMint
+    )]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Guest's token account (destination of the refund)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reservation.guest,
+        associated_token::token_program = token_program
+    )]
+    pub guest_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Host's token account (destination of the non-refundable share)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reservation.host,
+        associated_token::token_program = token_program
+    )]
+    pub host_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Platform treasury account (receives the platform fee)
+    #[account(
+        mut,
+        address = platform_config.treasury @ CancelReservationError::InvalidTreasury
+    )]
+    pub platform_treasury: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[error_code]
+pub enum CancelReservationError {
+    #[msg("Only the guest can cancel their reservation")]
+    UnauthorizedGuest,
+    #[msg("Listing does not match the reservation")]
+    InvalidListing,
+    #[msg("Host does not own the listing")]
+    InvalidHost,
+    #[msg("Escrow accounts are required to settle this reservation")]
+    MissingEscrowAccounts,
+    #[msg("Escrow does not belong to this reservation")]
+    InvalidEscrow,
+    #[msg("Escrow vault does not belong to this escrow")]
+    InvalidEscrowVault,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Invalid mint provided")]
+    InvalidMint,
+    #[msg("Platform treasury does not match the platform config")]
+    InvalidTreasury,
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _check_in(ctx: Context<CheckIn>) -> Result<()> {
+    let reservation = &mut ctx.accounts.reservation;
+
+    // An unpaid stay is left to expire_reservation, it cannot be started
+    require!(
+        reservation.payment_status == PaymentStatus::Paid,
+        CheckInError::NotPaid
+    );
+
+    // Check-in opens on the start date and closes once the stay is over
+    let now = Clock::get()?.unix_timestamp as u64;
+    require!(now >= reservation.start_date, CheckInError::CheckInNotOpen);
+    require!(now < reservation.end_date, CheckInError::StayEnded);
+
+    reservation.transition_to(ReservationStatus::CheckedIn)?;
+    reservation.checked_in_at = now;
+
+    msg!("Reservation checked in:");
+    msg!("Listing: {}", reservation.listing);
+    msg!("Checked in by: {}", ctx.accounts.authority.key());
+
+    emit_cpi!(ReservationCheckedIn {
+        reservation: reservation.key(),
+        listing: reservation.listing,
+        checked_in_by: ctx.accounts.authority.key(),
+        checked_in_at: now,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CheckIn<'info> {
+    /// Either the guest or the host of the reservation
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == reservation.guest
+            || authority.key() == reservation.host @ CheckInError::Unauthorized
+    )]
+    pub reservation: Account<'info, Reservation>,
+}
+
+#[error_code]
+pub enum CheckInError {
+    #[msg("Only the guest or the host can check in")]
+    Unauthorized,
+    #[msg("Check-in opens on the reservation start date")]
+    CheckInNotOpen,
+    #[msg("The stay has already ended")]
+    StayEnded,
+    #[msg("The reservation has not been paid")]
+    NotPaid,
+}
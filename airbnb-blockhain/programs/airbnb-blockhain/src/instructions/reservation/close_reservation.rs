@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _close_reservation(ctx: Context<CloseReservation>) -> Result<()> {
+    let reservation = &ctx.accounts.reservation;
+
+    // Only finished reservations can be closed
+    require!(
+        reservation.status == ReservationStatus::Cancelled
+            || reservation.status == ReservationStatus::Completed,
+        CloseReservationError::ReservationNotFinished
+    );
+
+    // A linked escrow must be settled first so no funds are orphaned
+    if let Some(linked_escrow) = reservation.payment_escrow {
+        let Some(payment_escrow) = &ctx.accounts.payment_escrow else {
+            return err!(CloseReservationError::MissingEscrow);
+        };
+        require_keys_eq!(
+            payment_escrow.key(),
+            linked_escrow,
+            CloseReservationError::InvalidEscrow
+        );
+        require!(
+            payment_escrow.status == EscrowStatus::Released
+                || payment_escrow.status == EscrowStatus::Refunded,
+            CloseReservationError::EscrowNotSettled
+        );
+    }
+
+    let listing = &mut ctx.accounts.listing;
+    listing.open_reservation_count = math::checked_sub(listing.open_reservation_count, 1)?;
+    let guest = &mut ctx.accounts.guest;
+    guest.open_reservation_count = math::checked_sub(guest.open_reservation_count, 1)?;
+
+    msg!("Reservation closed: {}", reservation.key());
+
+    emit_cpi!(ReservationClosed {
+        reservation: reservation.key(),
+        guest: reservation.guest,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseReservation<'info> {
+    #[account(mut)]
+    pub reservation_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = reservation_authority,
+        constraint = reservation.guest == reservation_authority.key() @ CloseReservationError::UnauthorizedGuest
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    /// Guest role of the signer, tracks its open reservations
+    #[account(
+        mut,
+        seeds = [GUEST_SEED.as_bytes(), reservation_authority.key().as_ref()],
+        bump = guest.bump,
+    )]
+    pub guest: Account<'info, Guest>,
+
+    /// The listing the reservation was made for, tracks its open reservations
+    #[account(
+        mut,
+        address = reservation.listing @ CloseReservationError::InvalidListing
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// The reservation's entry in the listing's booking index, closed with it
+    #[account(
+        mut,
+        close = reservation_authority,
+        seeds = [
+            BOOKING_INDEX_SEED.as_bytes(),
+            reservation.listing.as_ref(),
+            &reservation.booking_index.to_le_bytes(),
+        ],
+        bump = booking_index.bump,
+    )]
+    pub booking_index: Account<'info, BookingIndex>,
+
+    /// Escrow linked to the reservation, if any
+    pub payment_escrow: Option<Account<'info, PaymentEscrow>>,
+}
+
+#[error_code]
+pub enum CloseReservationError {
+    #[msg("Only the guest can close their reservation")]
+    UnauthorizedGuest,
+    #[msg("Listing does not match the reservation")]
+    InvalidListing,
+    #[msg("Reservation is not cancelled or completed")]
+    ReservationNotFinished,
+    #[msg("The reservation's escrow must be provided")]
+    MissingEscrow,
+    #[msg("Escrow does not belong to this reservation")]
+    InvalidEscrow,
+    #[msg("Escrow has not been released or refunded")]
+    EscrowNotSettled,
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _complete_reservation(ctx: Context<CompleteReservation>) -> Result<()> {
+    let reservation = &mut ctx.accounts.reservation;
+
+    // A stay the guest never checked in to is a no-show, the host cannot complete it
+    require!(
+        reservation.status == ReservationStatus::CheckedIn,
+        CompleteReservationError::NotCheckedIn
+    );
+
+    // Check-out happens once the last night is over
+    let now = Clock::get()?.unix_timestamp as u64;
+    require!(
+        now >= reservation.end_date,
+        CompleteReservationError::StayNotOver
+    );
+
+    reservation.transition_to(ReservationStatus::Completed)?;
+    reservation.completed_at = now;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.total_bookings = math::checked_add(listing.total_bookings, 1)?;
+
+    msg!("Reservation completed:");
+    msg!("Listing: {}", reservation.listing);
+    msg!("Total bookings: {}", listing.total_bookings);
+
+    emit_cpi!(ReservationCompleted {
+        reservation: reservation.key(),
+        listing: listing.key(),
+        completed_at: now,
+        total_bookings: listing.total_bookings,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CompleteReservation<'info> {
+    /// Either the guest or the host of the reservation
+    pub authority: Signer<'info>,
+
+    /// The listing that was booked
+    #[account(
+        mut,
+        address = reservation.listing @ CompleteReservationError::InvalidListing
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == reservation.guest
+            || authority.key() == reservation.host @ CompleteReservationError::Unauthorized
+    )]
+    pub reservation: Account<'info, Reservation>,
+}
+
+#[error_code]
+pub enum CompleteReservationError {
+    #[msg("Only the guest or the host can complete a reservation")]
+    Unauthorized,
+    #[msg("Listing does not match the reservation")]
+    InvalidListing,
+    #[msg("The guest has not checked in")]
+    NotCheckedIn,
+    #[msg("The stay has not ended yet")]
+    StayNotOver,
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _confirm_reservation(ctx: Context<ConfirmReservation>) -> Result<()> {
+    let reservation = &mut ctx.accounts.reservation;
+
+    reservation.transition_to(ReservationStatus::Confirmed)?;
+
+    msg!("Reservation confirmed:");
+    msg!("Listing: {}", reservation.listing);
+    msg!("Guest: {}", reservation.guest);
+
+    emit_cpi!(ReservationConfirmed {
+        reservation: reservation.key(),
+        listing: reservation.listing,
+        guest: reservation.guest,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ConfirmReservation<'info> {
+    pub host_authority: Signer<'info>,
+
+    /// Host profile of the listing owner
+    #[account(
+        constraint = host.host_author == host_authority.key() @ ConfirmReservationError::UnauthorizedHost
+    )]
+    pub host: Account<'info, Host>,
+
+    /// The listing being booked
+    #[account(
+        address = reservation.listing @ ConfirmReservationError::InvalidListing,
+        constraint = listing.host == host.key() @ ConfirmReservationError::UnauthorizedHost
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(mut)]
+    pub reservation: Account<'info, Reservation>,
+}
+
+#[error_code]
+pub enum ConfirmReservationError {
+    #[msg("Only the listing's host can confirm a reservation")]
+    UnauthorizedHost,
+    #[msg("Listing does not match the reservation")]
+    InvalidListing,
+}
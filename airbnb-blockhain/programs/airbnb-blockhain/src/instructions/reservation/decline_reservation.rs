@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::token::transfer_from_escrow_vault;
+use crate::events::*;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _decline_reservation(ctx: Context<DeclineReservation>) -> Result<()> {
+    let reservation_key = ctx.accounts.reservation.key();
+
+    // Confirmed stays are cancelled through host_cancel_reservation, which records the penalty
+    require!(
+        ctx.accounts.reservation.status == ReservationStatus::Pending,
+        DeclineReservationError::ReservationNotPending
+    );
+
+    // A linked escrow must be passed in so it cannot be left funded
+    if let Some(linked_escrow) = ctx.accounts.reservation.payment_escrow {
+        require!(
+            ctx.accounts
+                .payment_escrow
+                .as_ref()
+                .is_some_and(|payment_escrow| payment_escrow.key() == linked_escrow),
+            DeclineReservationError::MissingEscrowAccounts
+        );
+    }
+
+    // Refund any funded escrow in full, the guest is not at fault
+    let mut refund_amount = 0;
+    if let Some(payment_escrow) = &mut ctx.accounts.payment_escrow {
+        require!(
+            payment_escrow.reservation == reservation_key,
+            DeclineReservationError::InvalidEscrow
+        );
+        require!(
+            payment_escrow.status != EscrowStatus::Disputed,
+            DeclineReservationError::EscrowDisputed
+        );
+
+        if payment_escrow.status == EscrowStatus::Funded {
+            let (Some(escrow_vault), Some(mint), Some(guest_token_account)) = (
+                &ctx.accounts.escrow_vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.guest_token_account,
+            ) else {
+                return err!(DeclineReservationError::MissingEscrowAccounts);
+            };
+            require_keys_eq!(
+                escrow_vault.key(),
+                payment_escrow.vault_address(&payment_escrow.key())?,
+                DeclineReservationError::InvalidEscrowVault
+            );
+
+            refund_amount = escrow_vault.amount;
+            transfer_from_escrow_vault(
+                &ctx.accounts.token_program,
+                escrow_vault,
+                guest_token_account,
+                payment_escrow,
+                mint,
+                refund_amount,
+                ctx.accounts.platform_config.mint_decimals,
+            )?;
+
+            payment_escrow.set_status(EscrowStatus::Refunded)?;
+            ctx.accounts.platform_config.escrow_settled()?;
+            ctx.accounts.reservation.set_payment_status(PaymentStatus::Refunded)?;
+            msg!("Escrow refunded: {}", refund_amount);
+        }
+    }
+
+    let reservation = &mut ctx.accounts.reservation;
+    reservation.transition_to(ReservationStatus::Cancelled)?;
+
+    // Give the nights back to the calendar
+    ctx.accounts.availability.load_mut()?.release(
+        reservation.start_date / SECONDS_PER_DAY,
+        reservation.end_date / SECONDS_PER_DAY,
+    );
+
+    msg!("Reservation declined:");
+    msg!("Listing: {}", reservation.listing);
+    msg!("Guest: {}", reservation.guest);
+
+    emit_cpi!(ReservationDeclined {
+        reservation: reservation.key(),
+        listing: reservation.listing,
+        guest: reservation.guest,
+        refund_amount,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DeclineReservation<'info> {
+    pub host_authority: Signer<'info>,
+
+    /// Host profile of the listing owner
+    #[account(
+        constraint = host.host_author == host_authority.key() @ DeclineReservationError::UnauthorizedHost
+    )]
+    pub host: Box<Account<'info, Host>>,
+
+    /// The listing being booked
+    #[account(
+        address = reservation.listing @ DeclineReservationError::InvalidListing,
+        constraint = listing.host == host.key() @ DeclineReservationError::UnauthorizedHost
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(mut)]
+    pub reservation: Box<Account<'info, Reservation>>,
+
+    /// Calendar of the listing
+    #[account(
+        mut,
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump = availability.load()?.bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+
+    /// Global platform configuration (mint, decimals)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// Escrow funded for this reservation, if any
+    #[account(mut)]
+    pub payment_escrow: Option<Box<Account<'info, PaymentEscrow>>>,
+
+    /// Vault holding the escrowed funds
+    #[account(mut)]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ DeclineReservationError::InvalidMint
+    )]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Guest's token account (destination of refund)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reservation.guest,
+        associated_token::token_program = token_program
+    )]
+    pub guest_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[error_code]
+pub enum DeclineReservationError {
+    #[msg("Only the listing's host can decline a reservation")]
+    UnauthorizedHost,
+    #[msg("Listing does not match the reservation")]
+    InvalidListing,
+    #[msg("Only pending reservations can be declined, cancel confirmed ones instead")]
+    ReservationNotPending,
+    #[msg("Escrow accounts are required to refund this reservation")]
+    MissingEscrowAccounts,
+    #[msg("Escrow does not belong to this reservation")]
+    InvalidEscrow,
+    #[msg("Escrow vault does not belong to this escrow")]
+    InvalidEscrowVault,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Invalid mint provided")]
+    InvalidMint,
+}
@@ -0,0 +1,223 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::token::transfer_from_escrow_vault;
+use crate::events::*;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _expire_reservation(ctx: Context<ExpireReservation>) -> Result<()> {
+    let reservation_key = ctx.accounts.reservation.key();
+    let reservation = &ctx.accounts.reservation;
+    let now = Clock::get()?.unix_timestamp as u64;
+
+    // Unpaid past the payment deadline, or still unanswered past the host response deadline
+    let unpaid = reservation.payment_status != PaymentStatus::Paid
+        && matches!(
+            reservation.status,
+            ReservationStatus::Pending | ReservationStatus::Confirmed
+        )
+        && now > reservation.payment_deadline;
+    let unanswered = reservation.status == ReservationStatus::Pending
+        && now > reservation.host_response_deadline;
+    require!(unpaid || unanswered, ExpireReservationError::NotExpired);
+
+    // A linked escrow must be passed in so it cannot be left funded
+    if let Some(linked_escrow) = reservation.payment_escrow {
+        require!(
+            ctx.accounts
+                .payment_escrow
+                .as_ref()
+                .is_some_and(|payment_escrow| payment_escrow.key() == linked_escrow),
+            ExpireReservationError::MissingEscrowAccounts
+        );
+    }
+
+    // Refund any funded escrow in full, the guest is not at fault
+    let mut refund_amount = 0;
+    if let Some(payment_escrow) = &mut ctx.accounts.payment_escrow {
+        require!(
+            payment_escrow.reservation == reservation_key,
+            ExpireReservationError::InvalidEscrow
+        );
+        require!(
+            payment_escrow.status != EscrowStatus::Disputed,
+            ExpireReservationError::EscrowDisputed
+        );
+
+        if payment_escrow.status == EscrowStatus::Funded {
+            let (Some(escrow_vault), Some(mint), Some(guest_token_account)) = (
+                &ctx.accounts.escrow_vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.guest_token_account,
+            ) else {
+                return err!(ExpireReservationError::MissingEscrowAccounts);
+            };
+            require_keys_eq!(
+                escrow_vault.key(),
+                payment_escrow.vault_address(&payment_escrow.key())?,
+                ExpireReservationError::InvalidEscrowVault
+            );
+
+            refund_amount = escrow_vault.amount;
+            transfer_from_escrow_vault(
+                &ctx.accounts.token_program,
+                escrow_vault,
+                guest_token_account,
+                payment_escrow,
+                mint,
+                refund_amount,
+                ctx.accounts.platform_config.mint_decimals,
+            )?;
+
+            payment_escrow.set_status(EscrowStatus::Refunded)?;
+            ctx.accounts.platform_config.escrow_settled()?;
+            msg!("Escrow refunded: {}", refund_amount);
+        }
+    }
+
+    let reservation = &mut ctx.accounts.reservation;
+    reservation.transition_to(ReservationStatus::Cancelled)?;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.open_reservation_count = math::checked_sub(listing.open_reservation_count, 1)?;
+    let guest_account = &mut ctx.accounts.guest_account;
+    guest_account.open_reservation_count =
+        math::checked_sub(guest_account.open_reservation_count, 1)?;
+
+    // Give the nights back to the calendar
+    ctx.accounts.availability.load_mut()?.release(
+        reservation.start_date / SECONDS_PER_DAY,
+        reservation.end_date / SECONDS_PER_DAY,
+    );
+
+    msg!("Reservation expired:");
+    msg!("Listing: {}", reservation.listing);
+    msg!("Guest: {}", reservation.guest);
+
+    emit_cpi!(ReservationExpired {
+        reservation: reservation_key,
+        listing: reservation.listing,
+        guest: reservation.guest,
+        refund_amount,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExpireReservation<'info> {
+    /// Anyone can expire an overdue reservation
+    pub cranker: Signer<'info>,
+
+    /// Guest who paid the reservation's rent, receives it back
+    /// CHECK: Only receives lamports, must match the reservation guest
+    #[account(
+        mut,
+        address = reservation.guest @ ExpireReservationError::InvalidGuest
+    )]
+    pub guest: UncheckedAccount<'info>,
+
+    /// Guest role of the reservation's guest, tracks its open reservations
+    #[account(
+        mut,
+        seeds = [GUEST_SEED.as_bytes(), reservation.guest.as_ref()],
+        bump = guest_account.bump,
+    )]
+    pub guest_account: Box<Account<'info, Guest>>,
+
+    /// Closed back to the guest once expired
+    #[account(
+        mut,
+        close = guest,
+    )]
+    pub reservation: Box<Account<'info, Reservation>>,
+
+    /// The reservation's entry in the listing's booking index, closed with it
+    #[account(
+        mut,
+        close = guest,
+        seeds = [
+            BOOKING_INDEX_SEED.as_bytes(),
+            reservation.listing.as_ref(),
+            &reservation.booking_index.to_le_bytes(),
+        ],
+        bump = booking_index.bump,
+    )]
+    pub booking_index: Box<Account<'info, BookingIndex>>,
+
+    /// The listing the reservation was made for, tracks its open reservations
+    #[account(
+        mut,
+        address = reservation.listing @ ExpireReservationError::InvalidListing
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    /// Calendar of the listing
+    #[account(
+        mut,
+        seeds = [AVAILABILITY_SEED.as_bytes(), reservation.listing.as_ref()],
+        bump = availability.load()?.bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+
+    /// Global platform configuration (mint, decimals)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// Escrow funded for this reservation, if any
+    #[account(mut)]
+    pub payment_escrow: Option<Box<Account<'info, PaymentEscrow>>>,
+
+    /// Vault holding the escrowed funds
+    #[account(mut)]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ ExpireReservationError::InvalidMint
+    )]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Guest's token account (destination of refund)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reservation.guest,
+        associated_token::token_program = token_program
+    )]
+    pub guest_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[error_code]
+pub enum ExpireReservationError {
+    #[msg("Reservation has not passed its payment or host response deadline")]
+    NotExpired,
+    #[msg("Guest does not match the reservation")]
+    InvalidGuest,
+    #[msg("Listing does not match the reservation")]
+    InvalidListing,
+    #[msg("Escrow accounts are required to refund this reservation")]
+    MissingEscrowAccounts,
+    #[msg("Escrow does not belong to this reservation")]
+    InvalidEscrow,
+    #[msg("Escrow vault does not belong to this escrow")]
+    InvalidEscrowVault,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Invalid mint provided")]
+    InvalidMint,
+}
@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::instructions::token::transfer_from_escrow_vault;
+use crate::events::*;
+use crate::math;
+use crate::states::*;
+
+#[inline(never)]
+pub fn _host_cancel_reservation(ctx: Context<HostCancelReservation>) -> Result<()> {
+    let reservation_key = ctx.accounts.reservation.key();
+
+    // Pending reservations are declined, cancelling is for stays the host already confirmed
+    require!(
+        ctx.accounts.reservation.status == ReservationStatus::Confirmed,
+        HostCancelReservationError::ReservationNotConfirmed
+    );
+
+    // A linked escrow must be passed in so it cannot be left funded
+    if let Some(linked_escrow) = ctx.accounts.reservation.payment_escrow {
+        require!(
+            ctx.accounts
+                .payment_escrow
+                .as_ref()
+                .is_some_and(|payment_escrow| payment_escrow.key() == linked_escrow),
+            HostCancelReservationError::MissingEscrowAccounts
+        );
+    }
+
+    // Refund the whole vault, platform fee included, the guest is not at fault
+    let mut refund_amount = 0;
+    if let Some(payment_escrow) = &mut ctx.accounts.payment_escrow {
+        require!(
+            payment_escrow.reservation == reservation_key,
+            HostCancelReservationError::InvalidEscrow
+        );
+        require!(
+            payment_escrow.status != EscrowStatus::Disputed,
+            HostCancelReservationError::EscrowDisputed
+        );
+
+        if payment_escrow.status == EscrowStatus::Funded {
+            let (Some(escrow_vault), Some(mint), Some(guest_token_account)) = (
+                &ctx.accounts.escrow_vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.guest_token_account,
+            ) else {
+                return err!(HostCancelReservationError::MissingEscrowAccounts);
+            };
+            require_keys_eq!(
+                escrow_vault.key(),
+                payment_escrow.vault_address(&payment_escrow.key())?,
+                HostCancelReservationError::InvalidEscrowVault
+            );
+
+            refund_amount = escrow_vault.amount;
+            transfer_from_escrow_vault(
+                &ctx.accounts.token_program,
+                escrow_vault,
+                guest_token_account,
+                payment_escrow,
+                mint,
+                refund_amount,
+                ctx.accounts.platform_config.mint_decimals,
+            )?;
+
+            payment_escrow.set_status(EscrowStatus::Refunded)?;
+            ctx.accounts.platform_config.escrow_settled()?;
+            ctx.accounts.reservation.set_payment_status(PaymentStatus::Refunded)?;
+        }
+    }
+
+    // Record the cancellation, the penalty is withheld from the host's future payouts
+    let penalty = math::bps_of(
+        ctx.accounts.reservation.total_price,
+        ctx.accounts.platform_config.host_cancel_penalty_bps,
+    )?;
+    let host = &mut ctx.accounts.host;
+    host.cancellations = math::checked_add(host.cancellations, 1)?;
+    host.last_cancelled_at = Clock::get()?.unix_timestamp as u64;
+    host.pending_penalty = math::checked_add(host.pending_penalty, penalty)?;
+
+    let reservation = &mut ctx.accounts.reservation;
+    reservation.transition_to(ReservationStatus::Cancelled)?;
+
+    // Give the nights back to the calendar
+    ctx.accounts.availability.load_mut()?.release(
+        reservation.start_date / SECONDS_PER_DAY,
+        reservation.end_date / SECONDS_PER_DAY,
+    );
+
+    msg!("Reservation cancelled by host:");
+    msg!("Refund amount: {}", refund_amount);
+    msg!("Penalty: {}", penalty);
+    msg!("Host cancellations: {}", host.cancellations);
+
+    emit_cpi!(ReservationCancelledByHost {
+        reservation: reservation.key(),
+        listing: reservation.listing,
+        guest: reservation.guest,
+        host: host.key(),
+        refund_amount,
+        penalty,
+        cancellations: host.cancellations,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct HostCancelReservation<'info> {
+    pub host_authority: Signer<'info>,
+
+    /// Host profile of the listing owner, records the cancellation
+    #[account(
+        mut,
+        constraint = host.host_author == host_authority.key() @ HostCancelReservationError::UnauthorizedHost
+    )]
+    pub host: Box<Account<'info, Host>>,
+
+    /// The listing being booked
+    #[account(
+        address = reservation.listing @ HostCancelReservationError::InvalidListing,
+        constraint = listing.host == host.key() @ HostCancelReservationError::UnauthorizedHost
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(mut)]
+    pub reservation: Box<Account<'info, Reservation>>,
+
+    /// Calendar of the listing
+    #[account(
+        mut,
+        seeds = [AVAILABILITY_SEED.as_bytes(), listing.key().as_ref()],
+        bump = availability.load()?.bump,
+    )]
+    pub availability: AccountLoader<'info, ListingAvailability>,
+
+    /// Global platform configuration (mint, decimals, penalty)
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED.as_bytes()],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// Escrow funded for this reservation, if any
+    #[account(mut)]
+    pub payment_escrow: Option<Box<Account<'info, PaymentEscrow>>>,
+
+    /// Vault holding the escrowed funds
+    #[account(mut)]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The payment token mint
+    #[account(
+        address = platform_config.accepted_mint @ HostCancelReservationError::InvalidMint
+    )]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Guest's token account (destination of refund)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reservation.guest,
+        associated_token::token_program = token_program
+    )]
+    pub guest_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[error_code]
+pub enum HostCancelReservationError {
+    #[msg("Only the listing's host can cancel a reservation")]
+    UnauthorizedHost,
+    #[msg("Listing does not match the reservation")]
+    InvalidListing,
+    #[msg("Only confirmed reservations can be cancelled by the host, decline pending ones")]
+    ReservationNotConfirmed,
+    #[msg("Escrow accounts are required to refund this reservation")]
+    MissingEscrowAccounts,
+    #[msg("Escrow does not belong to this reservation")]
+    InvalidEscrow,
+    #[msg("Escrow vault does not belong to this escrow")]
+    InvalidEscrowVault,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Invalid mint provided")]
+    InvalidMint,
+}
@@ -0,0 +1,19 @@
+pub mod book_listing;
+pub mod cancel_reservation;
+pub mod check_in;
+pub mod close_reservation;
+pub mod complete_reservation;
+pub mod confirm_reservation;
+pub mod decline_reservation;
+pub mod expire_reservation;
+pub mod host_cancel_reservation;
+
+pub use book_listing::*;
+pub use cancel_reservation::*;
+pub use check_in::*;
+pub use close_reservation::*;
+pub use complete_reservation::*;
+pub use confirm_reservation::*;
+pub use decline_reservation::*;
+pub use expire_reservation::*;
+pub use host_cancel_reservation::*;
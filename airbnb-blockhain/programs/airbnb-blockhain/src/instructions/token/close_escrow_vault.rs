@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{
+        close_account,
+        spl_token_2022::{
+            extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions},
+            state::Account as SplTokenAccount,
+        },
+        CloseAccount,
+        Token2022,
+    },
+    token_interface::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint, Mint, TokenAccount},
+};
+
+use crate::states::*;
+
+/// Close an empty escrow vault and send its rent to `destination`
+/// Transfer fees withheld in the vault are harvested to the mint first,
+/// Token 2022 refuses to close accounts that still hold withheld fees
+pub fn close_escrow_vault<'info>(
+    token_program: &Program<'info, Token2022>,
+    escrow_vault: &InterfaceAccount<'info, TokenAccount>,
+    payment_escrow: &Account<'info, PaymentEscrow>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let withheld_amount = {
+        let vault_info = escrow_vault.to_account_info();
+        let vault_data = vault_info.try_borrow_data()?;
+        let vault_state = StateWithExtensions::<SplTokenAccount>::unpack(&vault_data)?;
+        vault_state
+            .get_extension::<TransferFeeAmount>()
+            .map(|fee_amount| u64::from(fee_amount.withheld_amount))
+            .unwrap_or(0)
+    };
+
+    if withheld_amount > 0 {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: token_program.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+            ),
+            vec![escrow_vault.to_account_info()],
+        )?;
+    }
+
+    let escrow_id_bytes = payment_escrow.escrow_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        PAYMENT_ESCROW_SEED.as_bytes(),
+        payment_escrow.reservation.as_ref(),
+        &escrow_id_bytes,
+        &[payment_escrow.bump],
+    ]];
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow_vault.to_account_info(),
+            destination: destination.clone(),
+            authority: payment_escrow.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    msg!("Closed escrow vault, harvested {} withheld tokens", withheld_amount);
+    Ok(())
+}
@@ -10,8 +10,14 @@ use anchor_spl::{
 };
 // use mpl_token_metadata::instructions::CreateMetadataAccountV3; // TODO
 
+use crate::errors::TokenError;
+use crate::events::*;
+use crate::states::BPS_DENOMINATOR;
+
 
 pub fn _initialize_token(ctx: Context<InitializeTokenContext>, fee_bps: u16, max_fee: u64) -> Result<()> {
+    require!(fee_bps as u64 <= BPS_DENOMINATOR, TokenError::InvalidFeeBps);
+
     // programs variables
     let system_program = &ctx.accounts.system_program;
     let token_program = &ctx.accounts.token_program;
@@ -102,9 +108,17 @@ pub fn _initialize_token(ctx: Context<InitializeTokenContext>, fee_bps: u16, max
     //     None,
     // )?;
 
+    emit_cpi!(TokenInitialized {
+        mint: mint.key(),
+        creator: creator.key(),
+        fee_bps,
+        max_fee,
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeTokenContext<'info> {
     #[account(mut)]
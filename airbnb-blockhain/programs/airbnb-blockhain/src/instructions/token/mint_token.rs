@@ -1,10 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, token_2022::{mint_to, MintTo, Token2022}, token_interface::{Mint, TokenAccount}};
 
+use crate::errors::TokenError;
+use crate::events::*;
+
 pub fn _mint_token(ctx: Context<MintTokenContext>, amount: u64) -> Result<()> {
-    if amount == 0 {
-        panic!("Invalid amount!");
-    }
+    require!(amount > 0, TokenError::InvalidAmount);
 
     let recipient_ata = &ctx.accounts.recipient_ata;
     let token_program = &ctx.accounts.token_program;
@@ -25,9 +26,16 @@ pub fn _mint_token(ctx: Context<MintTokenContext>, amount: u64) -> Result<()> {
         amount,
     )?;
 
+    emit_cpi!(TokenMinted {
+        mint: mint.key(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct MintTokenContext<'info> {
     #[account(mut)]
@@ -1,9 +1,13 @@
+pub mod close_escrow_vault;
 pub mod initialize_token;
 pub mod mint_token;
+pub mod transfer_fee;
 pub mod transfer_token;
 pub mod withdraw_token;
 
+pub use close_escrow_vault::*;
 pub use initialize_token::*;
 pub use mint_token::*;
+pub use transfer_fee::*;
 pub use transfer_token::*;
 pub use withdraw_token::*;
\ No newline at end of file
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+        state::Mint as SplMint,
+    },
+    token_interface::Mint,
+};
+
+/// Read the mint's TransferFeeConfig extension, if it has one
+pub fn get_transfer_fee_config(mint: &InterfaceAccount<Mint>) -> Result<Option<TransferFeeConfig>> {
+    let mint_info = mint.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+    Ok(mint_state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Smallest amount to send so that exactly `net_amount` arrives after the transfer fee
+pub fn gross_up_for_transfer_fee(mint: &InterfaceAccount<Mint>, net_amount: u64) -> Result<u64> {
+    let Some(transfer_fee_config) = get_transfer_fee_config(mint)? else {
+        return Ok(net_amount);
+    };
+    let epoch = Clock::get()?.epoch;
+    transfer_fee_config
+        .get_epoch_fee(epoch)
+        .calculate_pre_fee_amount(net_amount)
+        .ok_or_else(|| TransferFeeError::FeeCalculationFailed.into())
+}
+
+#[error_code]
+pub enum TransferFeeError {
+    #[msg("Transfer fee calculation overflowed")]
+    FeeCalculationFailed,
+    #[msg("Escrow vault cannot cover the amount plus its transfer fee")]
+    InsufficientVaultBalance,
+}
@@ -1,33 +1,156 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     token_2022::{transfer_checked, TransferChecked, Token2022},
-    token_interface::{Mint, TokenAccount},
+    token_interface::{transfer_checked_with_fee, Mint, TokenAccount, TransferCheckedWithFee},
 };
 
-/// Transfer tokens from guest to platform treasury
-/// Uses Token 2022 transfer_checked for enhanced security by validating mint and decimals
-pub fn transfer_to_platform_treasury<'info>(
+use crate::instructions::token::{get_transfer_fee_config, gross_up_for_transfer_fee, TransferFeeError};
+use crate::states::*;
+
+/// Transfer tokens from guest to the escrow vault
+/// The guest pays the Token 2022 transfer fee on top, so the vault receives exactly `amount`
+/// (decimals come from the platform config)
+pub fn transfer_to_escrow_vault<'info>(
     token_program: &Program<'info, Token2022>,
     guest_token_account: &InterfaceAccount<'info, TokenAccount>,
-    platform_treasury: &InterfaceAccount<'info, TokenAccount>,
+    escrow_vault: &InterfaceAccount<'info, TokenAccount>,
     guest_authority: &Signer<'info>,
     mint: &InterfaceAccount<'info, Mint>,
     amount: u64,
+    decimals: u8,
 ) -> Result<()> {
-    transfer_checked(
-        CpiContext::new(
-            token_program.to_account_info(),
-            TransferChecked {
-                from: guest_token_account.to_account_info(),
-                to: platform_treasury.to_account_info(),
-                authority: guest_authority.to_account_info(),
-                mint: mint.to_account_info(),
-            },
-        ),
+    let gross_amount = gross_up_for_transfer_fee(mint, amount)?;
+
+    transfer_checked_for_mint(
+        token_program,
+        guest_token_account.to_account_info(),
+        escrow_vault.to_account_info(),
+        guest_authority.to_account_info(),
+        mint,
+        gross_amount,
+        decimals,
+        &[],
+    )?;
+
+    msg!("Transferred {} tokens to escrow vault ({} sent)", amount, gross_amount);
+    Ok(())
+}
+
+/// Transfer tokens out of an escrow vault, the recipient bears the transfer fee
+/// The vault authority is the PaymentEscrow PDA, so the transfer is signed with its seeds
+pub fn transfer_from_escrow_vault<'info>(
+    token_program: &Program<'info, Token2022>,
+    escrow_vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    payment_escrow: &Account<'info, PaymentEscrow>,
+    mint: &InterfaceAccount<'info, Mint>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let escrow_id_bytes = payment_escrow.escrow_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        PAYMENT_ESCROW_SEED.as_bytes(),
+        payment_escrow.reservation.as_ref(),
+        &escrow_id_bytes,
+        &[payment_escrow.bump],
+    ]];
+
+    transfer_checked_for_mint(
+        token_program,
+        escrow_vault.to_account_info(),
+        destination.to_account_info(),
+        payment_escrow.to_account_info(),
+        mint,
         amount,
-        9, // Standard token decimals for this project
+        decimals,
+        signer_seeds,
     )?;
 
-    msg!("Transferred {} tokens to platform treasury", amount);
+    msg!("Transferred {} tokens out of escrow vault", amount);
     Ok(())
 }
+
+/// Transfer out of an escrow vault so that exactly `net_amount` arrives after the transfer fee
+/// Fails rather than underpay when more than `available` would have to be sent; returns the amount sent
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_net_from_escrow_vault<'info>(
+    token_program: &Program<'info, Token2022>,
+    escrow_vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    payment_escrow: &Account<'info, PaymentEscrow>,
+    mint: &InterfaceAccount<'info, Mint>,
+    net_amount: u64,
+    available: u64,
+    decimals: u8,
+) -> Result<u64> {
+    let gross_amount = gross_up_for_transfer_fee(mint, net_amount)?;
+    require!(gross_amount <= available, TransferFeeError::InsufficientVaultBalance);
+
+    transfer_from_escrow_vault(
+        token_program,
+        escrow_vault,
+        destination,
+        payment_escrow,
+        mint,
+        gross_amount,
+        decimals,
+    )?;
+
+    Ok(gross_amount)
+}
+
+/// transfer_checked_with_fee when the mint charges a transfer fee, so the fee is asserted
+/// by the token program, plain transfer_checked otherwise
+#[allow(clippy::too_many_arguments)]
+fn transfer_checked_for_mint<'info>(
+    token_program: &Program<'info, Token2022>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    match get_transfer_fee_config(mint)? {
+        Some(transfer_fee_config) => {
+            let fee = transfer_fee_config
+                .calculate_epoch_fee(Clock::get()?.epoch, amount)
+                .ok_or(TransferFeeError::FeeCalculationFailed)?;
+            transfer_checked_with_fee(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    TransferCheckedWithFee {
+                        token_program_id: token_program.to_account_info(),
+                        source: from,
+                        mint: mint.to_account_info(),
+                        destination: to,
+                        authority,
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                decimals,
+                fee,
+            )
+        }
+        None => transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from,
+                    to,
+                    authority,
+                    mint: mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            decimals,
+        ),
+    }
+}
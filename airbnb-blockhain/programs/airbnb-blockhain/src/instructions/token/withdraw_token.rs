@@ -10,6 +10,8 @@ use anchor_spl::{
     }
 };
 
+use crate::events::*;
+
 pub fn _withdraw_token(ctx: Context<WithdrawTokenContext>) -> Result<()> {
     let from_ata = &ctx.accounts.from_ata;
     let creator_ata = &ctx.accounts.creator_ata;
@@ -32,9 +34,16 @@ pub fn _withdraw_token(ctx: Context<WithdrawTokenContext>) -> Result<()> {
         vec![from_ata.to_account_info()]
     )?;
 
+    emit_cpi!(WithheldTokensWithdrawn {
+        mint: mint.key(),
+        from: from_ata.key(),
+        destination: creator_ata.key(),
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct WithdrawTokenContext<'info> {
     #[account(mut)]
@@ -1,144 +1,343 @@
 use anchor_lang::prelude::*;
 use crate::instructions::*;
 
+pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod math;
 pub mod states;
+pub mod validation;
 
 declare_id!("5FeA9qBzmvEDreexhEMmivcz9KccuhCZaqWWVYxtkgm9");
 
-#[program]
-pub mod airbnb_blockhain {
+pub use program_module::*;
+
+// The #[program] macro still expands to the deprecated AccountInfo::realloc next to the
+// module it annotates, so the allow wraps the expansion instead of the whole crate
+#[allow(deprecated)]
+mod program_module {
     use super::*;
 
-    pub fn initialize_host(
-        ctx: Context<InitializeHost>,
-        name: String,
-        email: String,
-        image: String,
-        hashed_password: String,
-        created_at: u64,
-    ) -> Result<()> {
-        _initialize_host(
-            ctx,
-            name,
-            email,
-            image,
-            hashed_password,
-            created_at,
-        )
-    }
+    #[program]
+    pub mod airbnb_blockhain {
+        use super::*;
 
-    pub fn initialize_guest(
-        ctx: Context<InitializeGuest>,
-        name: String,
-        email: String,
-        image_url: String,
-        hashed_password: String,
-        created_at: u64,
-        phone_number: String,
-        date_of_birth: u64,
-        preferred_language: String,
-    ) -> Result<()> {
-        _initialize_guest(
-            ctx,
-            name,
-            email,
-            image_url,
-            hashed_password,
-            created_at,
-            phone_number,
-            date_of_birth,
-            preferred_language,
-        )
-    }
+        pub fn initialize_user_profile(
+            ctx: Context<InitializeUserProfile>,
+            name: String,
+            image_url: String,
+            profile_commitment: [u8; 32],
+            metadata_uri: String,
+            preferred_language: String,
+        ) -> Result<()> {
+            _initialize_user_profile(
+                ctx,
+                name,
+                image_url,
+                profile_commitment,
+                metadata_uri,
+                preferred_language,
+            )
+        }
 
-    pub fn initialize_listing(
-        ctx: Context<InitializeListing>,
-        title: String,
-        description: String,
-        image_url: String,
-        created_at: u64,
-        category: String,
-        room_count: u8,
-        bathroom_count: u8,
-        guest_count: u8,
-        location_value: String,
-        total_bookings: u64,
-        is_active: bool,
-        price: u64,
-    ) -> Result<()> {
-        _initialize_listing(
-            ctx,
-            title,
-            description,
-            image_url,
-            created_at,
-            category,
-            room_count,
-            bathroom_count,
-            guest_count,
-            location_value,
-            total_bookings,
-            is_active,
-            price,
-        )
-    }
+        pub fn update_user_profile(
+            ctx: Context<UpdateUserProfile>,
+            name: Option<String>,
+            image_url: Option<String>,
+            profile_commitment: Option<[u8; 32]>,
+            metadata_uri: Option<String>,
+            preferred_language: Option<String>,
+        ) -> Result<()> {
+            _update_user_profile(
+                ctx,
+                name,
+                image_url,
+                profile_commitment,
+                metadata_uri,
+                preferred_language,
+            )
+        }
 
-    pub fn initialize_reservation(
-        ctx: Context<InitializeReservation>,
-        reservation_id: u64,
-        listing: Pubkey,
-        host: Pubkey,
-        start_date: u64,
-        end_date: u64,
-        guest_count: u8,
-        total_nights: u16,
-        price_per_night: u64,
-        total_price: u64,
-        status: states::ReservationStatus,
-        created_at: u64,
-        payment_status: states::PaymentStatus,
-    ) -> Result<()> {
-        _initialize_reservation(
-            ctx,
-            reservation_id,
-            listing,
-            host,
-            start_date,
-            end_date,
-            guest_count,
-            total_nights,
-            price_per_night,
-            total_price,
-            status,
-            created_at,
-            payment_status,
-        )
-    }
+        pub fn initialize_host(ctx: Context<InitializeHost>) -> Result<()> {
+            _initialize_host(ctx)
+        }
 
-    pub fn initialize_payment_escrow(
-        ctx: Context<InitializePaymentEscrow>,
-        escrow_id: u64,
-        amount: u64,
-        release_date: u64,
-    ) -> Result<()> {
-        _initialize_payment_escrow(ctx, escrow_id, amount, release_date)
-    }
+        pub fn initialize_guest(ctx: Context<InitializeGuest>) -> Result<()> {
+            _initialize_guest(ctx)
+        }
 
-    pub fn release_payment_escrow(
-        ctx: Context<ReleasePaymentEscrow>,
-    ) -> Result<()> {
-        _release_payment_escrow(ctx)
-    }
+        pub fn migrate_host(
+            ctx: Context<MigrateHost>,
+            profile_commitment: [u8; 32],
+            metadata_uri: String,
+        ) -> Result<()> {
+            _migrate_host(ctx, profile_commitment, metadata_uri)
+        }
 
-    pub fn initialize_token(ctx: Context<InitializeTokenContext>, fee_bps: u16, max_fee: u64) -> Result<()> {
-        _initialize_token(ctx, fee_bps, max_fee)
-    }
-    pub fn mint_token(ctx: Context<MintTokenContext>, amount: u64) -> Result<()> {
-        _mint_token(ctx, amount)
-    }
-    pub fn withdraw_token(ctx: Context<WithdrawTokenContext>) -> Result<()> {
-        _withdraw_token(ctx)
-    }
+        pub fn migrate_guest(
+            ctx: Context<MigrateGuest>,
+            profile_commitment: [u8; 32],
+            metadata_uri: String,
+            reservation_count: u64,
+        ) -> Result<()> {
+            _migrate_guest(ctx, profile_commitment, metadata_uri, reservation_count)
+        }
+
+        pub fn migrate_listing(
+            ctx: Context<MigrateListing>,
+            cancellation_policy: states::CancellationPolicy,
+        ) -> Result<()> {
+            _migrate_listing(ctx, cancellation_policy)
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        pub fn initialize_listing(
+            ctx: Context<InitializeListing>,
+            title: String,
+            description: String,
+            image_url: String,
+            category: String,
+            room_count: u8,
+            bathroom_count: u8,
+            guest_count: u8,
+            location_value: String,
+            is_active: bool,
+            price: u64,
+            cancellation_policy: states::CancellationPolicy,
+        ) -> Result<()> {
+            _initialize_listing(
+                ctx,
+                title,
+                description,
+                image_url,
+                category,
+                room_count,
+                bathroom_count,
+                guest_count,
+                location_value,
+                is_active,
+                price,
+                cancellation_policy,
+            )
+        }
+
+        pub fn initialize_reservation(
+            ctx: Context<InitializeReservation>,
+            start_date: u64,
+            end_date: u64,
+            guest_count: u8,
+        ) -> Result<()> {
+            _initialize_reservation(ctx, start_date, end_date, guest_count)
+        }
+
+        pub fn book_listing(
+            ctx: Context<BookListing>,
+            escrow_id: u64,
+            start_date: u64,
+            end_date: u64,
+            guest_count: u8,
+            release_date: u64,
+        ) -> Result<()> {
+            _book_listing(ctx, escrow_id, start_date, end_date, guest_count, release_date)
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        pub fn update_listing(
+            ctx: Context<UpdateListing>,
+            title: Option<String>,
+            description: Option<String>,
+            image_url: Option<String>,
+            category: Option<String>,
+            room_count: Option<u8>,
+            bathroom_count: Option<u8>,
+            guest_count: Option<u8>,
+            location_value: Option<String>,
+            price: Option<u64>,
+            cancellation_policy: Option<states::CancellationPolicy>,
+        ) -> Result<()> {
+            _update_listing(
+                ctx,
+                title,
+                description,
+                image_url,
+                category,
+                room_count,
+                bathroom_count,
+                guest_count,
+                location_value,
+                price,
+                cancellation_policy,
+            )
+        }
+
+        pub fn set_listing_active(
+            ctx: Context<SetListingActive>,
+            is_active: bool,
+        ) -> Result<()> {
+            _set_listing_active(ctx, is_active)
+        }
+
+        pub fn block_listing_dates(
+            ctx: Context<UpdateListingAvailability>,
+            start_date: u64,
+            end_date: u64,
+        ) -> Result<()> {
+            _block_listing_dates(ctx, start_date, end_date)
+        }
+
+        pub fn unblock_listing_dates(
+            ctx: Context<UpdateListingAvailability>,
+            start_date: u64,
+            end_date: u64,
+        ) -> Result<()> {
+            _unblock_listing_dates(ctx, start_date, end_date)
+        }
+
+        pub fn confirm_reservation(ctx: Context<ConfirmReservation>) -> Result<()> {
+            _confirm_reservation(ctx)
+        }
+
+        pub fn check_in(ctx: Context<CheckIn>) -> Result<()> {
+            _check_in(ctx)
+        }
 
+        pub fn complete_reservation(ctx: Context<CompleteReservation>) -> Result<()> {
+            _complete_reservation(ctx)
+        }
+
+        pub fn cancel_reservation(ctx: Context<CancelReservation>) -> Result<()> {
+            _cancel_reservation(ctx)
+        }
+
+        pub fn host_cancel_reservation(ctx: Context<HostCancelReservation>) -> Result<()> {
+            _host_cancel_reservation(ctx)
+        }
+
+        pub fn decline_reservation(ctx: Context<DeclineReservation>) -> Result<()> {
+            _decline_reservation(ctx)
+        }
+
+        pub fn expire_reservation(ctx: Context<ExpireReservation>) -> Result<()> {
+            _expire_reservation(ctx)
+        }
+
+        pub fn close_host(ctx: Context<CloseHost>) -> Result<()> {
+            _close_host(ctx)
+        }
+
+        pub fn close_guest(ctx: Context<CloseGuest>) -> Result<()> {
+            _close_guest(ctx)
+        }
+
+        pub fn close_user_profile(ctx: Context<CloseUserProfile>) -> Result<()> {
+            _close_user_profile(ctx)
+        }
+
+        pub fn close_listing(ctx: Context<CloseListing>) -> Result<()> {
+            _close_listing(ctx)
+        }
+
+        pub fn close_reservation(ctx: Context<CloseReservation>) -> Result<()> {
+            _close_reservation(ctx)
+        }
+
+        pub fn close_payment_escrow(ctx: Context<ClosePaymentEscrow>) -> Result<()> {
+            _close_payment_escrow(ctx)
+        }
+
+        pub fn initialize_platform(
+            ctx: Context<InitializePlatform>,
+            fee_bps: u16,
+            release_authority: Pubkey,
+            arbiter: Pubkey,
+            refund_fee_policy: states::RefundFeePolicy,
+            host_cancel_penalty_bps: u16,
+            crank_bounty_bps: u16,
+        ) -> Result<()> {
+            _initialize_platform(
+                ctx,
+                fee_bps,
+                release_authority,
+                arbiter,
+                refund_fee_policy,
+                host_cancel_penalty_bps,
+                crank_bounty_bps,
+            )
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        pub fn update_platform_config(
+            ctx: Context<UpdatePlatformConfig>,
+            fee_bps: Option<u16>,
+            release_authority: Option<Pubkey>,
+            arbiter: Option<Pubkey>,
+            new_admin: Option<Pubkey>,
+            refund_fee_policy: Option<states::RefundFeePolicy>,
+            host_cancel_penalty_bps: Option<u16>,
+            crank_bounty_bps: Option<u16>,
+        ) -> Result<()> {
+            _update_platform_config(
+                ctx,
+                fee_bps,
+                release_authority,
+                arbiter,
+                new_admin,
+                refund_fee_policy,
+                host_cancel_penalty_bps,
+                crank_bounty_bps,
+            )
+        }
+
+        pub fn initialize_payment_escrow(
+            ctx: Context<InitializePaymentEscrow>,
+            escrow_id: u64,
+            amount: u64,
+            release_date: u64,
+        ) -> Result<()> {
+            _initialize_payment_escrow(ctx, escrow_id, amount, release_date)
+        }
+
+        pub fn release_payment_escrow(
+            ctx: Context<ReleasePaymentEscrow>,
+        ) -> Result<()> {
+            _release_payment_escrow(ctx)
+        }
+
+        pub fn crank_release_payment_escrow(
+            ctx: Context<CrankReleasePaymentEscrow>,
+        ) -> Result<()> {
+            _crank_release_payment_escrow(ctx)
+        }
+
+        pub fn refund_payment_escrow(
+            ctx: Context<RefundPaymentEscrow>,
+        ) -> Result<()> {
+            _refund_payment_escrow(ctx)
+        }
+
+        pub fn open_dispute(
+            ctx: Context<OpenDispute>,
+            reason_hash: [u8; 32],
+            evidence_uris: Vec<String>,
+        ) -> Result<()> {
+            _open_dispute(ctx, reason_hash, evidence_uris)
+        }
+
+        pub fn resolve_dispute(
+            ctx: Context<ResolveDispute>,
+            guest_share_bps: u16,
+        ) -> Result<()> {
+            _resolve_dispute(ctx, guest_share_bps)
+        }
+
+        pub fn initialize_token(ctx: Context<InitializeTokenContext>, fee_bps: u16, max_fee: u64) -> Result<()> {
+            _initialize_token(ctx, fee_bps, max_fee)
+        }
+        pub fn mint_token(ctx: Context<MintTokenContext>, amount: u64) -> Result<()> {
+            _mint_token(ctx, amount)
+        }
+        pub fn withdraw_token(ctx: Context<WithdrawTokenContext>) -> Result<()> {
+            _withdraw_token(ctx)
+        }
+    }
 }
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::MathError;
+use crate::states::BPS_DENOMINATOR;
+
+/// `a + b`, failing with `MathOverflow` instead of wrapping or panicking
+pub fn checked_add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or_else(|| error!(MathError::MathOverflow))
+}
+
+/// `a - b`, failing with `MathOverflow` on underflow
+pub fn checked_sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or_else(|| error!(MathError::MathOverflow))
+}
+
+/// `a * b`, failing with `MathOverflow` when the product does not fit in a u64
+pub fn checked_mul(a: u64, b: u64) -> Result<u64> {
+    a.checked_mul(b).ok_or_else(|| error!(MathError::MathOverflow))
+}
+
+/// `amount * bps / 10_000` rounded down. The product is taken in u128 so it never
+/// overflows, the result only fails to fit when `bps` is above 100%
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    let share = (amount as u128) * (bps as u128) / (BPS_DENOMINATOR as u128);
+    u64::try_from(share).map_err(|_| error!(MathError::MathOverflow))
+}
+
+/// Splits `amount` into `(platform_fee, host_amount)` for a fee in basis points
+pub fn split_platform_fee(amount: u64, fee_bps: u16) -> Result<(u64, u64)> {
+    let platform_fee = bps_of(amount, fee_bps)?;
+    let host_amount = checked_sub(amount, platform_fee)?;
+    Ok((platform_fee, host_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const MAX_BPS: u16 = BPS_DENOMINATOR as u16;
+
+    proptest! {
+        #[test]
+        fn checked_add_matches_u128(a in any::<u64>(), b in any::<u64>()) {
+            let wide = a as u128 + b as u128;
+            match checked_add(a, b) {
+                Ok(sum) => prop_assert_eq!(sum as u128, wide),
+                Err(_) => prop_assert!(wide > u64::MAX as u128),
+            }
+        }
+
+        #[test]
+        fn checked_sub_fails_only_on_underflow(a in any::<u64>(), b in any::<u64>()) {
+            match checked_sub(a, b) {
+                Ok(diff) => prop_assert_eq!(diff + b, a),
+                Err(_) => prop_assert!(b > a),
+            }
+        }
+
+        #[test]
+        fn checked_mul_matches_u128(a in any::<u64>(), b in any::<u64>()) {
+            let wide = a as u128 * b as u128;
+            match checked_mul(a, b) {
+                Ok(product) => prop_assert_eq!(product as u128, wide),
+                Err(_) => prop_assert!(wide > u64::MAX as u128),
+            }
+        }
+
+        #[test]
+        fn bps_of_never_exceeds_amount(amount in any::<u64>(), bps in 0..=MAX_BPS) {
+            let share = bps_of(amount, bps).unwrap();
+            prop_assert!(share <= amount);
+            prop_assert_eq!(
+                share as u128,
+                amount as u128 * bps as u128 / BPS_DENOMINATOR as u128
+            );
+        }
+
+        #[test]
+        fn bps_of_above_full_share_never_panics(amount in any::<u64>(), bps in any::<u16>()) {
+            let wide = amount as u128 * bps as u128 / BPS_DENOMINATOR as u128;
+            match bps_of(amount, bps) {
+                Ok(share) => prop_assert_eq!(share as u128, wide),
+                Err(_) => prop_assert!(wide > u64::MAX as u128),
+            }
+        }
+
+        #[test]
+        fn split_platform_fee_conserves_amount(amount in any::<u64>(), fee_bps in 0..=MAX_BPS) {
+            let (platform_fee, host_amount) = split_platform_fee(amount, fee_bps).unwrap();
+            prop_assert_eq!(platform_fee as u128 + host_amount as u128, amount as u128);
+        }
+    }
+
+    #[test]
+    fn boundaries() {
+        assert_eq!(bps_of(u64::MAX, MAX_BPS).unwrap(), u64::MAX);
+        assert_eq!(bps_of(u64::MAX, 0).unwrap(), 0);
+        assert_eq!(split_platform_fee(u64::MAX, 500).unwrap().0, u64::MAX / 20);
+        assert!(bps_of(u64::MAX, MAX_BPS + 1).is_err());
+        assert!(checked_add(u64::MAX, 1).is_err());
+        assert!(checked_sub(0, 1).is_err());
+        assert!(checked_mul(u64::MAX, 2).is_err());
+    }
+}
@@ -1,14 +1,109 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ReservationError;
+use crate::math;
+
+pub const USER_PROFILE_SEED: &str = "USER_PROFILE_SEED";
 pub const HOST_SEED: &str = "HOST_SEED";
 pub const GUEST_SEED: &str = "GUEST_SEED";
 pub const LISTING_SEED: &str = "LISTING_SEED";
 pub const RESERVATION_SEED: &str = "RESERVATION_SEED";
 pub const PAYMENT_ESCROW_SEED: &str = "PAYMENT_ESCROW_SEED";
+pub const ESCROW_VAULT_SEED: &str = "ESCROW_VAULT_SEED";
+pub const PLATFORM_CONFIG_SEED: &str = "PLATFORM_CONFIG_SEED";
+pub const DISPUTE_SEED: &str = "DISPUTE_SEED";
+pub const AVAILABILITY_SEED: &str = "AVAILABILITY_SEED";
+pub const BOOKING_INDEX_SEED: &str = "BOOKING_INDEX_SEED";
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const SECONDS_PER_DAY: u64 = 86_400;
+pub const SECONDS_PER_HOUR: u64 = 3_600;
+pub const PARTIAL_REFUND_BPS: u16 = 5_000;
+pub const PAYMENT_WINDOW_SECONDS: u64 = 3_600;
+pub const HOST_RESPONSE_WINDOW_SECONDS: u64 = 86_400;
+pub const LISTING_TITLE_MAX_LEN: usize = 64;
+pub const LISTING_DESCRIPTION_MAX_LEN: usize = 300;
+pub const LISTING_IMAGE_URL_MAX_LEN: usize = 500;
+pub const LISTING_CATEGORY_MAX_LEN: usize = 32;
+pub const LISTING_LOCATION_MAX_LEN: usize = 32;
+pub const AVAILABILITY_WORDS: usize = 6;
+pub const AVAILABILITY_WINDOW_DAYS: u64 = AVAILABILITY_WORDS as u64 * 64;
+pub const MAX_EVIDENCE_URIS: usize = 5;
+pub const MAX_EVIDENCE_URI_LEN: usize = 200;
+pub const PROFILE_NAME_MAX_LEN: usize = 32;
+pub const PROFILE_IMAGE_URL_MAX_LEN: usize = 500;
+pub const PROFILE_METADATA_URI_MAX_LEN: usize = 200;
+pub const PREFERRED_LANGUAGE_MAX_LEN: usize = 32;
+
+pub const ROLE_HOST: u8 = 1 << 0;
+pub const ROLE_GUEST: u8 = 1 << 1;
 
+/// One per wallet, holds the identity shared by every role the wallet takes on
+#[account]
+#[derive(InitSpace)]
+pub struct UserProfile {
+    pub authority: Pubkey,
+    #[max_len(PROFILE_NAME_MAX_LEN)]
+    pub name: String,
+    #[max_len(PROFILE_IMAGE_URL_MAX_LEN)]
+    pub image_url: String,
+    pub profile_commitment: [u8; 32], // Hash of the off-chain profile and a salt
+    #[max_len(PROFILE_METADATA_URI_MAX_LEN)]
+    pub metadata_uri: String,
+    #[max_len(PREFERRED_LANGUAGE_MAX_LEN)]
+    pub preferred_language: String,
+    pub roles: u8, // ROLE_* flags, each set while its extension account exists
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub bump: u8,
+}
+
+impl UserProfile {
+    pub fn has_role(&self, role: u8) -> bool {
+        self.roles & role == role
+    }
+}
+
+/// Host role extension of a UserProfile
 #[account]
 #[derive(InitSpace)]
 pub struct Host {
+    pub host_author: Pubkey,
+    pub profile: Pubkey,
+    pub created_at: u64,
+    pub listing_count: u64,        // Used in listing PDA seeds, never decreases
+    pub active_listing_count: u64, // Listings not yet closed
+    pub cancellations: u64,        // Confirmed reservations cancelled by the host
+    pub last_cancelled_at: u64,
+    pub pending_penalty: u64,      // Withheld from future payouts
+    pub bump: u8,
+}
+
+impl Host {
+    /// Withhold as much of the outstanding cancellation penalty as `payout` covers
+    /// Returns the amount withheld, which stays with the platform
+    pub fn withhold_penalty(&mut self, payout: u64) -> u64 {
+        let withheld = self.pending_penalty.min(payout);
+        self.pending_penalty -= withheld;
+        withheld
+    }
+}
+
+/// Guest role extension of a UserProfile
+#[account]
+#[derive(InitSpace)]
+pub struct Guest {
+    pub guest_author: Pubkey,
+    pub profile: Pubkey,
+    pub created_at: u64,
+    pub reservation_count: u64, // Used in reservation PDA seeds, never decreases
+    pub open_reservation_count: u64, // Reservation accounts not yet closed
+    pub bump: u8,
+}
+
+/// Host layout before personal data was moved off-chain, only read by migrate_host
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct LegacyHost {
     pub host_author: Pubkey,
     #[max_len(32)]
     pub name: String,
@@ -23,9 +118,9 @@ pub struct Host {
     pub bump: u8,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Guest {
+/// Guest layout before personal data was moved off-chain, only read by migrate_guest
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct LegacyGuest {
     pub guest_author: Pubkey,
     #[max_len(32)]
     pub name: String,
@@ -37,7 +132,7 @@ pub struct Guest {
     pub hashed_password: String,
     pub created_at: u64,
     #[max_len(32)]
-    pub phone_number: String,    
+    pub phone_number: String,
     pub date_of_birth: u64,
     #[max_len(32)]
     pub preferred_language: String,
@@ -47,6 +142,33 @@ pub struct Guest {
 #[account]
 #[derive(InitSpace)]
 pub struct Listing {
+    pub host: Pubkey,
+    #[max_len(LISTING_TITLE_MAX_LEN)]
+    pub title: String,
+    #[max_len(LISTING_DESCRIPTION_MAX_LEN)]
+    pub description: String,
+    #[max_len(LISTING_IMAGE_URL_MAX_LEN)]
+    pub image_url: String,
+    pub created_at: u64,
+    pub updated_at: u64, // Last edit by the host
+    #[max_len(LISTING_CATEGORY_MAX_LEN)]
+    pub category: String,
+    pub room_count: u8,
+    pub bathroom_count: u8,
+    pub guest_count: u8,
+    #[max_len(LISTING_LOCATION_MAX_LEN)]
+    pub location_value: String, // ISO 3166-1 alpha-2 country code, e.g. US, GB, FR
+    pub total_bookings: u64,
+    pub is_active: bool,
+    pub price: u64,
+    pub cancellation_policy: CancellationPolicy,
+    pub booking_count: u64, // Used in booking index PDA seeds, never decreases
+    pub open_reservation_count: u64, // Reservation accounts not yet closed
+}
+
+/// Listing layout before timestamps, policies and availability, only read by migrate_listing
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct LegacyListing {
     pub host: Pubkey,
     #[max_len(64)]
     pub title: String,
@@ -61,12 +183,167 @@ pub struct Listing {
     pub bathroom_count: u8,
     pub guest_count: u8,
     #[max_len(32)]
-    pub location_value: String, // US, UK, FR
+    pub location_value: String,
     pub total_bookings: u64,
     pub is_active: bool,
     pub price: u64,
 }
 
+/// How much of the stay a guest gets back when cancelling, cutoffs are hours before check-in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq)]
+pub enum CancellationPolicy {
+    /// Full refund until the cutoff, nothing after
+    Flexible { full_refund_hours: u32 },
+    /// Full refund until the cutoff, half refund until check-in
+    Moderate { full_refund_hours: u32 },
+    /// Half refund until the cutoff, nothing after
+    Strict { partial_refund_hours: u32 },
+    /// Nothing is refunded
+    NonRefundable,
+}
+
+impl CancellationPolicy {
+    /// Basis points of the stay refunded when cancelling at `now` for a stay starting at `check_in`
+    pub fn refund_bps(&self, now: u64, check_in: u64) -> u16 {
+        if now >= check_in {
+            return 0;
+        }
+        let hours_before = (check_in - now) / SECONDS_PER_HOUR;
+        match *self {
+            CancellationPolicy::Flexible { full_refund_hours }
+            | CancellationPolicy::Moderate { full_refund_hours }
+                if hours_before >= full_refund_hours as u64 =>
+            {
+                BPS_DENOMINATOR as u16
+            }
+            CancellationPolicy::Moderate { .. } => PARTIAL_REFUND_BPS,
+            CancellationPolicy::Strict { partial_refund_hours }
+                if hours_before >= partial_refund_hours as u64 =>
+            {
+                PARTIAL_REFUND_BPS
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Entry `index` of a listing's bookings, lets clients enumerate a listing's reservations
+/// by deriving `[BOOKING_INDEX_SEED, listing, index]` for every index below `booking_count`
+#[account]
+#[derive(InitSpace)]
+pub struct BookingIndex {
+    pub listing: Pubkey,
+    pub reservation: Pubkey,
+    pub index: u64,
+    pub bump: u8,
+}
+
+/// Rolling calendar of nights for a listing, one bit per night
+/// Bit for day `d` lives at `d % AVAILABILITY_WINDOW_DAYS`
+#[account(zero_copy)]
+pub struct ListingAvailability {
+    pub listing: Pubkey,
+    pub window_start_day: u64,               // First day (unix days) covered
+    pub booked: [u64; AVAILABILITY_WORDS],   // Nights held by reservations
+    pub blocked: [u64; AVAILABILITY_WORDS],  // Nights blocked by the host
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl ListingAvailability {
+    fn bit(day: u64) -> (usize, u64) {
+        let index = day % AVAILABILITY_WINDOW_DAYS;
+        ((index / 64) as usize, 1u64 << (index % 64))
+    }
+
+    fn is_set(map: &[u64; AVAILABILITY_WORDS], day: u64) -> bool {
+        let (word, mask) = Self::bit(day);
+        map[word] & mask != 0
+    }
+
+    fn set(map: &mut [u64; AVAILABILITY_WORDS], day: u64, value: bool) {
+        let (word, mask) = Self::bit(day);
+        if value {
+            map[word] |= mask;
+        } else {
+            map[word] &= !mask;
+        }
+    }
+
+    /// Slide the window forward to `today`, freeing the bits of past nights
+    pub fn advance_window(&mut self, today: u64) {
+        if today <= self.window_start_day {
+            return;
+        }
+        let stale_end = today.min(self.window_start_day + AVAILABILITY_WINDOW_DAYS);
+        for day in self.window_start_day..stale_end {
+            Self::set(&mut self.booked, day, false);
+            Self::set(&mut self.blocked, day, false);
+        }
+        self.window_start_day = today;
+    }
+
+    fn require_in_window(&self, start_day: u64, end_day: u64) -> Result<()> {
+        require!(
+            start_day >= self.window_start_day
+                && end_day <= self.window_start_day + AVAILABILITY_WINDOW_DAYS,
+            ReservationError::OutsideAvailabilityWindow
+        );
+        Ok(())
+    }
+
+    /// True when no night in `[start_day, end_day)` is booked or blocked
+    pub fn is_free(&self, start_day: u64, end_day: u64) -> bool {
+        (start_day..end_day)
+            .all(|day| !Self::is_set(&self.booked, day) && !Self::is_set(&self.blocked, day))
+    }
+
+    /// Hold the nights `[start_day, end_day)` for a reservation
+    pub fn book(&mut self, start_day: u64, end_day: u64) -> Result<()> {
+        self.require_in_window(start_day, end_day)?;
+        require!(
+            self.is_free(start_day, end_day),
+            ReservationError::DatesUnavailable
+        );
+        for day in start_day..end_day {
+            Self::set(&mut self.booked, day, true);
+        }
+        Ok(())
+    }
+
+    /// Free the nights `[start_day, end_day)` of a cancelled reservation
+    /// Nights that already left the window are ignored
+    pub fn release(&mut self, start_day: u64, end_day: u64) {
+        let start_day = start_day.max(self.window_start_day);
+        let end_day = end_day.min(self.window_start_day + AVAILABILITY_WINDOW_DAYS);
+        for day in start_day..end_day {
+            Self::set(&mut self.booked, day, false);
+        }
+    }
+
+    /// Block the nights `[start_day, end_day)` for owner stays or maintenance
+    pub fn block(&mut self, start_day: u64, end_day: u64) -> Result<()> {
+        self.require_in_window(start_day, end_day)?;
+        require!(
+            (start_day..end_day).all(|day| !Self::is_set(&self.booked, day)),
+            ReservationError::DatesUnavailable
+        );
+        for day in start_day..end_day {
+            Self::set(&mut self.blocked, day, true);
+        }
+        Ok(())
+    }
+
+    /// Unblock the nights `[start_day, end_day)`
+    pub fn unblock(&mut self, start_day: u64, end_day: u64) -> Result<()> {
+        self.require_in_window(start_day, end_day)?;
+        for day in start_day..end_day {
+            Self::set(&mut self.blocked, day, false);
+        }
+        Ok(())
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Reservation {
@@ -81,20 +358,61 @@ pub struct Reservation {
     pub total_price: u64,
     pub status: ReservationStatus,
     pub created_at: u64,
+    pub updated_at: u64, // Last status or payment status change
     pub payment_status: PaymentStatus,
     // Payment-related fields
     pub payment_escrow: Option<Pubkey>,  // Link to escrow account
     pub token_amount: u64,               // Amount in tokens
     pub platform_fee: u64,               // Fee amount
+    pub checked_in_at: u64,
+    pub completed_at: u64,
+    pub payment_deadline: u64,       // Unpaid reservations can be expired after this
+    pub host_response_deadline: u64, // Unconfirmed reservations can be expired after this
+    pub booking_index: u64,          // Position in the listing's booking index
+    pub cancellation_policy: CancellationPolicy, // Listing policy when booked, later edits don't apply
     pub bump: u8,
 }
 
+impl Reservation {
+    /// Move the reservation to `next`, rejecting illegal transitions
+    pub fn transition_to(&mut self, next: ReservationStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(&next),
+            ReservationError::InvalidStatusTransition
+        );
+        self.status = next;
+        self.updated_at = Clock::get()?.unix_timestamp as u64;
+        Ok(())
+    }
+
+    pub fn set_payment_status(&mut self, payment_status: PaymentStatus) -> Result<()> {
+        self.payment_status = payment_status;
+        self.updated_at = Clock::get()?.unix_timestamp as u64;
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq)]
 pub enum ReservationStatus {
     Pending,
     Confirmed,
     Cancelled,
     Completed,
+    CheckedIn,
+}
+
+impl ReservationStatus {
+    /// Legal reservation status transitions
+    pub fn can_transition_to(&self, next: &ReservationStatus) -> bool {
+        matches!(
+            (self, next),
+            (ReservationStatus::Pending, ReservationStatus::Confirmed)
+                | (ReservationStatus::Pending, ReservationStatus::Cancelled)
+                | (ReservationStatus::Confirmed, ReservationStatus::Cancelled)
+                | (ReservationStatus::Confirmed, ReservationStatus::CheckedIn)
+                | (ReservationStatus::CheckedIn, ReservationStatus::Completed)
+        )
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq)]
@@ -107,6 +425,7 @@ pub enum PaymentStatus {
 #[account]
 #[derive(InitSpace)]
 pub struct PaymentEscrow {
+    pub escrow_id: u64,
     pub reservation: Pubkey,
     pub guest: Pubkey,
     pub host: Pubkey,
@@ -114,8 +433,32 @@ pub struct PaymentEscrow {
     pub platform_fee: u64,
     pub status: EscrowStatus,
     pub created_at: u64,
+    pub updated_at: u64,    // Last status change
     pub release_date: u64,  // When host gets paid
+    pub dispute: Option<Pubkey>, // Dispute raised on the escrow, closed with it
     pub bump: u8,
+    pub vault_bump: u8,     // Token account owned by this escrow PDA
+}
+
+impl PaymentEscrow {
+    pub fn set_status(&mut self, status: EscrowStatus) -> Result<()> {
+        self.status = status;
+        self.updated_at = Clock::get()?.unix_timestamp as u64;
+        Ok(())
+    }
+
+    /// Address of the vault owned by the escrow at `payment_escrow`
+    pub fn vault_address(&self, payment_escrow: &Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(
+            &[
+                ESCROW_VAULT_SEED.as_bytes(),
+                payment_escrow.as_ref(),
+                &[self.vault_bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| ProgramError::InvalidSeeds.into())
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq)]
@@ -124,4 +467,279 @@ pub enum EscrowStatus {
     Released,      // Host received payment
     Refunded,      // Guest got refund
     Disputed,      // Needs resolution
-}
\ No newline at end of file
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PlatformConfig {
+    pub admin: Pubkey,
+    pub fee_bps: u16,              // Platform fee charged on every escrow
+    pub accepted_mint: Pubkey,     // Only mint accepted for payments
+    pub mint_decimals: u8,
+    pub treasury: Pubkey,          // Token account that receives platform fees
+    pub release_authority: Pubkey, // Signer allowed to release escrowed payments
+    pub arbiter: Pubkey,           // Signer allowed to resolve disputes
+    pub refund_fee_policy: RefundFeePolicy,
+    pub host_cancel_penalty_bps: u16, // Charged on the total price when a host cancels
+    pub crank_bounty_bps: u16,        // Share of the platform fee paid to release crankers
+    pub open_escrow_count: u64,       // Escrows still holding funds, the mint is locked while any are open
+    pub updated_at: u64,
+    pub bump: u8,
+}
+
+impl PlatformConfig {
+    /// Count an escrow that was just funded
+    pub fn escrow_opened(&mut self) -> Result<()> {
+        self.open_escrow_count = math::checked_add(self.open_escrow_count, 1)?;
+        Ok(())
+    }
+
+    /// Count an escrow that was paid out or refunded
+    pub fn escrow_settled(&mut self) -> Result<()> {
+        self.open_escrow_count = math::checked_sub(self.open_escrow_count, 1)?;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq)]
+pub enum RefundFeePolicy {
+    RetainPlatformFee, // Guest gets the amount minus the platform fee
+    RefundPlatformFee, // Guest gets the full amount back
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub payment_escrow: Pubkey,
+    pub reservation: Pubkey,
+    pub opened_by: Pubkey,        // Guest or host of the escrow
+    pub reason_hash: [u8; 32],    // Hash of the off-chain dispute reason
+    #[max_len(MAX_EVIDENCE_URIS, MAX_EVIDENCE_URI_LEN)]
+    pub evidence_uris: Vec<String>,
+    pub status: DisputeStatus,
+    pub opened_at: u64,
+    pub resolved_at: u64,
+    pub guest_share_bps: u16,     // Share of the host amount returned to the guest
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq)]
+pub enum DisputeStatus {
+    Open,
+    Resolved,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    const TODAY: u64 = 20_000;
+
+    fn availability() -> ListingAvailability {
+        let mut availability = ListingAvailability::zeroed();
+        availability.window_start_day = TODAY;
+        availability
+    }
+
+    #[test]
+    fn booking_overlapping_nights_is_rejected() {
+        let mut availability = availability();
+        availability.book(TODAY + 10, TODAY + 15).unwrap();
+
+        assert_eq!(
+            availability.book(TODAY + 14, TODAY + 16).unwrap_err(),
+            ReservationError::DatesUnavailable.into()
+        );
+        assert_eq!(
+            availability.book(TODAY + 5, TODAY + 11).unwrap_err(),
+            ReservationError::DatesUnavailable.into()
+        );
+        // Check-out day is the next guest's check-in night
+        availability.book(TODAY + 15, TODAY + 20).unwrap();
+        availability.book(TODAY + 5, TODAY + 10).unwrap();
+    }
+
+    #[test]
+    fn released_nights_can_be_booked_again() {
+        let mut availability = availability();
+        availability.book(TODAY + 10, TODAY + 15).unwrap();
+        availability.release(TODAY + 10, TODAY + 15);
+
+        assert!(availability.is_free(TODAY + 10, TODAY + 15));
+        availability.book(TODAY + 12, TODAY + 18).unwrap();
+    }
+
+    #[test]
+    fn release_ignores_nights_outside_the_window() {
+        let mut availability = availability();
+        availability.book(TODAY, TODAY + 2).unwrap();
+        availability
+            .book(
+                TODAY + AVAILABILITY_WINDOW_DAYS - 2,
+                TODAY + AVAILABILITY_WINDOW_DAYS,
+            )
+            .unwrap();
+
+        // Would wrap onto the first nights if it were not clamped
+        availability.release(
+            TODAY + AVAILABILITY_WINDOW_DAYS - 2,
+            TODAY + AVAILABILITY_WINDOW_DAYS + 2,
+        );
+
+        assert!(!availability.is_free(TODAY, TODAY + 2));
+        assert!(availability.is_free(
+            TODAY + AVAILABILITY_WINDOW_DAYS - 2,
+            TODAY + AVAILABILITY_WINDOW_DAYS
+        ));
+    }
+
+    #[test]
+    fn blocked_and_booked_nights_exclude_each_other() {
+        let mut availability = availability();
+        availability.block(TODAY + 3, TODAY + 6).unwrap();
+        assert_eq!(
+            availability.book(TODAY + 5, TODAY + 8).unwrap_err(),
+            ReservationError::DatesUnavailable.into()
+        );
+
+        availability.book(TODAY + 6, TODAY + 8).unwrap();
+        assert_eq!(
+            availability.block(TODAY + 7, TODAY + 9).unwrap_err(),
+            ReservationError::DatesUnavailable.into()
+        );
+
+        availability.unblock(TODAY + 3, TODAY + 6).unwrap();
+        availability.book(TODAY + 3, TODAY + 6).unwrap();
+    }
+
+    #[test]
+    fn nights_outside_the_window_are_rejected() {
+        let mut availability = availability();
+        assert_eq!(
+            availability.book(TODAY - 1, TODAY + 1).unwrap_err(),
+            ReservationError::OutsideAvailabilityWindow.into()
+        );
+        assert_eq!(
+            availability
+                .block(
+                    TODAY + AVAILABILITY_WINDOW_DAYS - 1,
+                    TODAY + AVAILABILITY_WINDOW_DAYS + 1
+                )
+                .unwrap_err(),
+            ReservationError::OutsideAvailabilityWindow.into()
+        );
+        availability
+            .book(
+                TODAY + AVAILABILITY_WINDOW_DAYS - 1,
+                TODAY + AVAILABILITY_WINDOW_DAYS,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn advancing_the_window_reuses_the_bits_of_past_nights() {
+        let mut availability = availability();
+        availability.book(TODAY, TODAY + 5).unwrap();
+        availability.block(TODAY + 5, TODAY + 7).unwrap();
+        availability.book(TODAY + 100, TODAY + 103).unwrap();
+
+        availability.advance_window(TODAY + 7);
+
+        // Same ring slots as the nights that just left the window
+        let wrapped = TODAY + AVAILABILITY_WINDOW_DAYS;
+        assert!(availability.is_free(wrapped, wrapped + 7));
+        availability.book(wrapped, wrapped + 7).unwrap();
+        assert!(!availability.is_free(TODAY + 100, TODAY + 103));
+    }
+
+    #[test]
+    fn advancing_past_a_whole_window_clears_the_calendar() {
+        let mut availability = availability();
+        availability.book(TODAY + 10, TODAY + 20).unwrap();
+        availability.block(TODAY + 200, TODAY + 210).unwrap();
+
+        let today = TODAY + 2 * AVAILABILITY_WINDOW_DAYS + 17;
+        availability.advance_window(today);
+
+        assert_eq!(availability.window_start_day, today);
+        assert_eq!(availability.booked, [0; AVAILABILITY_WORDS]);
+        assert_eq!(availability.blocked, [0; AVAILABILITY_WORDS]);
+    }
+
+    #[test]
+    fn advancing_backwards_is_a_no_op() {
+        let mut availability = availability();
+        availability.book(TODAY, TODAY + 3).unwrap();
+
+        availability.advance_window(TODAY - 5);
+
+        assert_eq!(availability.window_start_day, TODAY);
+        assert!(!availability.is_free(TODAY, TODAY + 3));
+    }
+
+    const CHECK_IN: u64 = TODAY * SECONDS_PER_DAY;
+    const FULL: u16 = BPS_DENOMINATOR as u16;
+
+    /// Cancelling exactly `hours` before check-in, and one second later
+    fn at_cutoff(policy: &CancellationPolicy, hours: u64) -> (u16, u16) {
+        let cutoff = CHECK_IN - hours * SECONDS_PER_HOUR;
+        (
+            policy.refund_bps(cutoff, CHECK_IN),
+            policy.refund_bps(cutoff + 1, CHECK_IN),
+        )
+    }
+
+    #[test]
+    fn flexible_refunds_in_full_until_the_cutoff() {
+        let policy = CancellationPolicy::Flexible {
+            full_refund_hours: 24,
+        };
+        assert_eq!(at_cutoff(&policy, 24), (FULL, 0));
+        assert_eq!(policy.refund_bps(0, CHECK_IN), FULL);
+    }
+
+    #[test]
+    fn moderate_refunds_half_between_the_cutoff_and_check_in() {
+        let policy = CancellationPolicy::Moderate {
+            full_refund_hours: 120,
+        };
+        assert_eq!(at_cutoff(&policy, 120), (FULL, PARTIAL_REFUND_BPS));
+        assert_eq!(policy.refund_bps(CHECK_IN - 1, CHECK_IN), PARTIAL_REFUND_BPS);
+    }
+
+    #[test]
+    fn strict_refunds_half_until_the_cutoff() {
+        let policy = CancellationPolicy::Strict {
+            partial_refund_hours: 168,
+        };
+        assert_eq!(at_cutoff(&policy, 168), (PARTIAL_REFUND_BPS, 0));
+        assert_eq!(policy.refund_bps(0, CHECK_IN), PARTIAL_REFUND_BPS);
+    }
+
+    #[test]
+    fn non_refundable_never_refunds() {
+        assert_eq!(CancellationPolicy::NonRefundable.refund_bps(0, CHECK_IN), 0);
+    }
+
+    #[test]
+    fn nothing_is_refunded_from_check_in_on() {
+        let policies = [
+            CancellationPolicy::Flexible {
+                full_refund_hours: 0,
+            },
+            CancellationPolicy::Moderate {
+                full_refund_hours: 0,
+            },
+            CancellationPolicy::Strict {
+                partial_refund_hours: 0,
+            },
+        ];
+        for policy in &policies {
+            assert_eq!(policy.refund_bps(CHECK_IN, CHECK_IN), 0);
+            assert_eq!(policy.refund_bps(CHECK_IN + 1, CHECK_IN), 0);
+            // A zero-hour cutoff still applies until the stay starts
+            assert_ne!(policy.refund_bps(CHECK_IN - 1, CHECK_IN), 0);
+        }
+    }
+}
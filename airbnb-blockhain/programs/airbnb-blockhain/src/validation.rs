@@ -0,0 +1,277 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::{ListingError, ProfileError};
+use crate::states::*;
+
+/// URI schemes accepted for images, metadata and evidence
+const URI_SCHEMES: [&str; 3] = ["https://", "ipfs://", "ar://"];
+
+/// ISO 3166-1 alpha-2 codes, plus XK which the frontend's country list also offers
+const COUNTRY_CODES: [&str; 250] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "XK", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// An https, ipfs or arweave URI with something after the scheme and no whitespace
+pub fn is_uri(uri: &str) -> bool {
+    URI_SCHEMES.iter().any(|scheme| {
+        uri.strip_prefix(scheme)
+            .is_some_and(|rest| !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_graphic()))
+    })
+}
+
+pub fn is_country_code(code: &str) -> bool {
+    COUNTRY_CODES.contains(&code)
+}
+
+// Listings
+
+pub fn listing_title(title: &str) -> Result<()> {
+    require!(!title.trim().is_empty(), ListingError::EmptyTitle);
+    require!(title.len() <= LISTING_TITLE_MAX_LEN, ListingError::TitleTooLong);
+    Ok(())
+}
+
+pub fn listing_description(description: &str) -> Result<()> {
+    require!(
+        description.len() <= LISTING_DESCRIPTION_MAX_LEN,
+        ListingError::DescriptionTooLong
+    );
+    Ok(())
+}
+
+pub fn listing_image_url(image_url: &str) -> Result<()> {
+    require!(
+        image_url.len() <= LISTING_IMAGE_URL_MAX_LEN,
+        ListingError::ImageUrlTooLong
+    );
+    require!(is_uri(image_url), ListingError::InvalidImageUrl);
+    Ok(())
+}
+
+pub fn listing_category(category: &str) -> Result<()> {
+    require!(
+        category.len() <= LISTING_CATEGORY_MAX_LEN,
+        ListingError::CategoryTooLong
+    );
+    Ok(())
+}
+
+pub fn location_value(location_value: &str) -> Result<()> {
+    require!(
+        location_value.len() <= LISTING_LOCATION_MAX_LEN,
+        ListingError::LocationTooLong
+    );
+    require!(is_country_code(location_value), ListingError::InvalidCountryCode);
+    Ok(())
+}
+
+pub fn room_count(room_count: u8) -> Result<()> {
+    require!(room_count > 0, ListingError::InvalidRoomCount);
+    Ok(())
+}
+
+pub fn guest_count(guest_count: u8) -> Result<()> {
+    require!(guest_count > 0, ListingError::InvalidGuestCount);
+    Ok(())
+}
+
+pub fn price(price: u64) -> Result<()> {
+    require!(price > 0, ListingError::InvalidPrice);
+    Ok(())
+}
+
+// Profiles
+
+pub fn profile_name(name: &str) -> Result<()> {
+    require!(!name.trim().is_empty(), ProfileError::EmptyName);
+    require!(name.len() <= PROFILE_NAME_MAX_LEN, ProfileError::NameTooLong);
+    Ok(())
+}
+
+/// Empty means no picture
+pub fn profile_image_url(image_url: &str) -> Result<()> {
+    require!(
+        image_url.len() <= PROFILE_IMAGE_URL_MAX_LEN,
+        ProfileError::ImageUrlTooLong
+    );
+    require!(
+        image_url.is_empty() || is_uri(image_url),
+        ProfileError::InvalidImageUrl
+    );
+    Ok(())
+}
+
+pub fn metadata_uri(metadata_uri: &str) -> Result<()> {
+    require!(
+        metadata_uri.len() <= PROFILE_METADATA_URI_MAX_LEN,
+        ProfileError::MetadataUriTooLong
+    );
+    require!(is_uri(metadata_uri), ProfileError::InvalidMetadataUri);
+    Ok(())
+}
+
+/// A language tag such as `en` or `pt-BR`, empty means unset
+pub fn preferred_language(preferred_language: &str) -> Result<()> {
+    require!(
+        preferred_language.len() <= PREFERRED_LANGUAGE_MAX_LEN,
+        ProfileError::PreferredLanguageTooLong
+    );
+    require!(
+        preferred_language
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-'),
+        ProfileError::InvalidPreferredLanguage
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uris_need_a_known_scheme_and_a_path() {
+        assert!(is_uri("https://example.com/a.png"));
+        assert!(is_uri("ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"));
+        assert!(is_uri("ar://Gh4fVx3q"));
+
+        assert!(!is_uri("http://example.com/a.png"));
+        assert!(!is_uri("javascript:alert(1)"));
+        assert!(!is_uri("HTTPS://example.com"));
+        assert!(!is_uri("https://"));
+        assert!(!is_uri("https://example.com/a b.png"));
+        assert!(!is_uri("https://example.com/\n"));
+        assert!(!is_uri(""));
+    }
+
+    #[test]
+    fn country_codes_are_upper_case_iso_alpha_2() {
+        assert!(is_country_code("US"));
+        assert!(is_country_code("GB"));
+        assert!(is_country_code("XK"));
+
+        // The old placeholder for the United Kingdom
+        assert!(!is_country_code("UK"));
+        assert!(!is_country_code("us"));
+        assert!(!is_country_code("USA"));
+        assert!(!is_country_code(""));
+    }
+
+    #[test]
+    fn country_code_list_is_sorted_without_duplicates() {
+        assert!(COUNTRY_CODES.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn empty_strings() {
+        assert_eq!(listing_title("").unwrap_err(), ListingError::EmptyTitle.into());
+        assert_eq!(listing_title("  ").unwrap_err(), ListingError::EmptyTitle.into());
+        assert_eq!(profile_name("").unwrap_err(), ProfileError::EmptyName.into());
+        assert_eq!(
+            listing_image_url("").unwrap_err(),
+            ListingError::InvalidImageUrl.into()
+        );
+        assert_eq!(
+            metadata_uri("").unwrap_err(),
+            ProfileError::InvalidMetadataUri.into()
+        );
+
+        // Optional fields
+        listing_description("").unwrap();
+        listing_category("").unwrap();
+        profile_image_url("").unwrap();
+        preferred_language("").unwrap();
+    }
+
+    #[test]
+    fn oversized_strings() {
+        let at = |max: usize| "a".repeat(max);
+        let over = |max: usize| "a".repeat(max + 1);
+        let uri = |len: usize| format!("https://{}", "a".repeat(len - "https://".len()));
+
+        listing_title(&at(LISTING_TITLE_MAX_LEN)).unwrap();
+        assert_eq!(
+            listing_title(&over(LISTING_TITLE_MAX_LEN)).unwrap_err(),
+            ListingError::TitleTooLong.into()
+        );
+        listing_description(&at(LISTING_DESCRIPTION_MAX_LEN)).unwrap();
+        assert_eq!(
+            listing_description(&over(LISTING_DESCRIPTION_MAX_LEN)).unwrap_err(),
+            ListingError::DescriptionTooLong.into()
+        );
+        listing_category(&at(LISTING_CATEGORY_MAX_LEN)).unwrap();
+        assert_eq!(
+            listing_category(&over(LISTING_CATEGORY_MAX_LEN)).unwrap_err(),
+            ListingError::CategoryTooLong.into()
+        );
+        assert_eq!(
+            location_value(&over(LISTING_LOCATION_MAX_LEN)).unwrap_err(),
+            ListingError::LocationTooLong.into()
+        );
+        profile_name(&at(PROFILE_NAME_MAX_LEN)).unwrap();
+        assert_eq!(
+            profile_name(&over(PROFILE_NAME_MAX_LEN)).unwrap_err(),
+            ProfileError::NameTooLong.into()
+        );
+        preferred_language(&at(PREFERRED_LANGUAGE_MAX_LEN)).unwrap();
+        assert_eq!(
+            preferred_language(&over(PREFERRED_LANGUAGE_MAX_LEN)).unwrap_err(),
+            ProfileError::PreferredLanguageTooLong.into()
+        );
+
+        listing_image_url(&uri(LISTING_IMAGE_URL_MAX_LEN)).unwrap();
+        assert_eq!(
+            listing_image_url(&uri(LISTING_IMAGE_URL_MAX_LEN + 1)).unwrap_err(),
+            ListingError::ImageUrlTooLong.into()
+        );
+        profile_image_url(&uri(PROFILE_IMAGE_URL_MAX_LEN)).unwrap();
+        assert_eq!(
+            profile_image_url(&uri(PROFILE_IMAGE_URL_MAX_LEN + 1)).unwrap_err(),
+            ProfileError::ImageUrlTooLong.into()
+        );
+        metadata_uri(&uri(PROFILE_METADATA_URI_MAX_LEN)).unwrap();
+        assert_eq!(
+            metadata_uri(&uri(PROFILE_METADATA_URI_MAX_LEN + 1)).unwrap_err(),
+            ProfileError::MetadataUriTooLong.into()
+        );
+    }
+
+    #[test]
+    fn preferred_language_is_a_language_tag() {
+        preferred_language("en").unwrap();
+        preferred_language("pt-BR").unwrap();
+        assert_eq!(
+            preferred_language("en_US").unwrap_err(),
+            ProfileError::InvalidPreferredLanguage.into()
+        );
+        assert_eq!(
+            preferred_language("en US").unwrap_err(),
+            ProfileError::InvalidPreferredLanguage.into()
+        );
+    }
+
+    #[test]
+    fn zero_counts_and_prices_are_rejected() {
+        assert_eq!(room_count(0).unwrap_err(), ListingError::InvalidRoomCount.into());
+        assert_eq!(guest_count(0).unwrap_err(), ListingError::InvalidGuestCount.into());
+        assert_eq!(price(0).unwrap_err(), ListingError::InvalidPrice.into());
+        room_count(1).unwrap();
+        guest_count(1).unwrap();
+        price(1).unwrap();
+    }
+}
@@ -1,7 +1,11 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { AirbnbBlockhain } from "../target/types/airbnb_blockhain";
-import { PublicKey, Keypair, Connection } from '@solana/web3.js';
+import { PublicKey, Keypair, Connection, Transaction } from '@solana/web3.js';
+import { startAnchor, BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext } from "solana-bankrun";
+import { LiteSVM, TransactionMetadata } from "litesvm";
+import { LiteSVMProvider } from "anchor-litesvm";
 import { assert } from "chai";
 import * as fs from 'fs';
 import * as path from 'path';
@@ -9,8 +13,10 @@ import {
   TOKEN_2022_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddressSync,
+  createAssociatedTokenAccountIdempotentInstruction,
   getMint
 } from "@solana/spl-token";
+import IDL from "../target/idl/airbnb_blockhain.json";
 
 const HOST_SEED = "HOST_SEED";
 const GUEST_SEED = "GUEST_SEED";
@@ -18,6 +24,13 @@ const LISTING_SEED = "LISTING_SEED";
 const RESERVATION_SEED = "RESERVATION_SEED";
 const PAYMENT_ESCROW_SEED = "PAYMENT_ESCROW_SEED";
 const PLATFORM_TREASURY_SEED = "PLATFORM_TREASURY_SEED";
+const PLATFORM_CONFIG_SEED = "PLATFORM_CONFIG_SEED";
+const ESCROW_VAULT_SEED = "ESCROW_VAULT_SEED";
+const USER_PROFILE_SEED = "USER_PROFILE_SEED";
+const AVAILABILITY_SEED = "AVAILABILITY_SEED";
+const BOOKING_INDEX_SEED = "BOOKING_INDEX_SEED";
+const DISPUTE_SEED = "DISPUTE_SEED";
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
 
 describe("airbnb-blockhain", () => {
   // Configure the client.
@@ -94,34 +107,138 @@ describe("airbnb-blockhain", () => {
     platformTreasuryATA = result.platformTreasuryATA;
   });
 
+  it("Should refuse to initialize the platform from a wallet that is not the upgrade authority", async () => {
+    try {
+      await program.methods.initializePlatform(
+        500,
+        platformAuthority.publicKey,
+        platformAuthority.publicKey,
+        { retainPlatformFee: {} },
+        1000,
+        1000,
+      )
+      .accounts({
+        admin: platformAuthority.publicKey, // Not the wallet that deployed the program
+        programData: getProgramDataAddress(program.programId),
+        platformConfig: getPlatformConfigAddress(program.programId),
+        mint: mint.publicKey,
+        platformTreasury: platformTreasuryATA,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([platformAuthority])
+      .rpc({ commitment: "confirmed" });
+
+      assert.fail("Expected initialize_platform to fail for a non upgrade authority");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedAdmin");
+    }
+  });
+
+  it("Should initialize the platform config", async () => {
+    // The provider wallet deployed the program, so it is the upgrade authority
+    await program.methods.initializePlatform(
+      500,                         // fee_bps (5%)
+      platformAuthority.publicKey, // release_authority
+      platformAuthority.publicKey, // arbiter
+      { retainPlatformFee: {} },   // refund_fee_policy
+      1000,                        // host_cancel_penalty_bps (10%)
+      1000,                        // crank_bounty_bps (10% of the platform fee)
+    )
+    .accounts({
+      admin: provider.wallet.publicKey,
+      programData: getProgramDataAddress(program.programId),
+      platformConfig: getPlatformConfigAddress(program.programId),
+      mint: mint.publicKey,
+      platformTreasury: platformTreasuryATA,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc({ commitment: "confirmed" });
+
+    const config = await program.account.platformConfig.fetch(getPlatformConfigAddress(program.programId));
+    assert.strictEqual(config.feeBps, 500, "Platform fee should be 5%");
+    assert.strictEqual(config.acceptedMint.toString(), mint.publicKey.toString(), "Accepted mint should match");
+    assert.strictEqual(config.treasury.toString(), platformTreasuryATA.toString(), "Treasury should match");
+    assert.strictEqual(config.releaseAuthority.toString(), platformAuthority.publicKey.toString(), "Release authority should match");
+  });
+
   it("Should display mint tokens", async () => {
     const mintInfo = await getMintInfo(mint.publicKey, provider.connection);
     console.log("🪙 Mint Info:", mintInfo);
     console.log("🔍 View on Solana Explorer: https://explorer.solana.com/address/" + mint.publicKey.toString() + "?cluster=devnet");
   });
 
-  it("Should initialize a host with valid fields", async () => {
+  // Name and picture stay on-chain, personal data lives off-chain behind a commitment
+  async function initializeUserProfile(user: Keypair, name: string, imageUrl: string) {
+    await program.methods.initializeUserProfile(
+      name,
+      imageUrl,
+      Array.from(Keypair.generate().publicKey.toBytes()), // profile_commitment
+      `ipfs://profiles/${user.publicKey.toBase58()}`,     // metadata_uri
+      "en",                                               // preferred_language
+    )
+    .accounts({
+      authority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc({ commitment: "confirmed" });
+  }
+
+  it("Should refuse to initialize a host without a user profile", async () => {
     await airdrop(provider.connection, host.publicKey);
 
+    try {
+      await program.methods.initializeHost()
+      .accounts({
+        hostAuthority: host.publicKey,
+        userProfile: getUserProfileAddress(host.publicKey, program.programId),
+        host: getHostAddress(host.publicKey, program.programId)[0],
+        systemProgram: anchor.web3.SystemProgram.programId
+      })
+      .signers([host])
+      .rpc({ commitment: "confirmed" });
+
+      assert.fail("Expected the host role to require a user profile");
+    } catch (error) {
+      assert.include(error.toString(), "AccountNotInitialized");
+    }
+  });
+
+  it("Should initialize a host with valid fields", async () => {
     const [host_pkey, host_bump] = getHostAddress(
       host.publicKey,
       program.programId
     );
+    const userProfile = getUserProfileAddress(host.publicKey, program.programId);
 
-    await program.methods.initializeHost(
+    await initializeUserProfile(
+      host,
       "Teresa Biagiola",
-      "teresabiagiola@gmail.com",
       "https://a0.muscache.com/im/pictures/prohost-api/Hosting-1194641374145248817/original/39aa64fa-38c1-4204-b6b2-8e639e43fd87.jpeg?im_w=720",
-      "password123",
-      new BN(Date.now()),
-    )
-    .accounts({ hostAuthority: host.publicKey })
+    );
+
+    await program.methods.initializeHost()
+    .accounts({
+      hostAuthority: host.publicKey,
+      userProfile: userProfile,
+      host: host_pkey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
     .signers([host])
     .rpc({ commitment: "confirmed" });
 
     // Fetch and verify the created host
     const hostAccount = await program.account.host.fetch(host_pkey);
     assert.strictEqual(hostAccount.listingCount.toNumber(), 0, "Host should start with 0 listings");
+    assert.strictEqual(hostAccount.profile.toString(), userProfile.toString(), "Host should point at its profile");
+
+    // TEST: The profile now holds the host role
+    const profileAccount = await program.account.userProfile.fetch(userProfile);
+    assert.strictEqual(profileAccount.name, "Teresa Biagiola", "Profile name should match");
+    assert.strictEqual(profileAccount.roles & 1, 1, "Profile should hold the host role");
   });
 
   it("Should initialize a Listing for a host", async () => {
@@ -155,21 +272,22 @@ describe("airbnb-blockhain", () => {
       "Beautiful Beach House",
       "Stunning oceanfront property with amazing sunset views",
       "https://a0.muscache.com/im/pictures/ccb251a8-663d-4472-9127-c51c471a55fc.jpg",
-      new BN(Date.now()),
       "Beach",
       3,           // room_count
       2,           // bathroom_count  
       6,           // guest_count
       "FR",        // location_value
-      new BN(0),   // total_bookings
       true,        // is_active
       new BN(299), // price per night
+      { flexible: { fullRefundHours: 24 } }, // cancellation_policy
     )
     .accounts({
       listingAuthority: host.publicKey,  // The wallet signing the transaction
+      userProfile: getUserProfileAddress(host.publicKey, program.programId), // Must hold the host role
       host: host_pkey,                   // The Host account PDA (proves they're a registered host)
-      listing: listing_pkey,                    // ← Uncomment this
-      systemProgram: anchor.web3.SystemProgram.programId  // ← Add this
+      listing: listing_pkey,
+      availability: getAvailabilityAddress(listing_pkey, program.programId), // Calendar of booked nights
+      systemProgram: anchor.web3.SystemProgram.programId
     })
     .signers([host])
     .rpc({ commitment: "confirmed" });
@@ -177,6 +295,12 @@ describe("airbnb-blockhain", () => {
     // Fetch and verify the created listing
     const listingAccount = await program.account.listing.fetch(listing_pkey);
 
+    // TEST: Verify the listing fields set on-chain
+    assert.strictEqual(listingAccount.price.toNumber(), 299, "Listing price should match");
+    assert.strictEqual(listingAccount.cancellationPolicy.flexible.fullRefundHours, 24, "Cancellation policy should match");
+    assert.strictEqual(listingAccount.updatedAt.toString(), listingAccount.createdAt.toString(), "A new listing has not been edited");
+    assert.strictEqual(listingAccount.bookingCount.toNumber(), 0, "Listing should start with 0 bookings");
+
     // TEST: Verify host listing count incremented to 1
     const updatedHostAccount = await program.account.host.fetch(host_pkey);
     assert.strictEqual(updatedHostAccount.listingCount.toNumber(), 1, "Host listing count should be 1 after creating first listing");
@@ -290,20 +414,21 @@ describe("airbnb-blockhain", () => {
         listing.title,
         listing.description,
         listing.image_url,
-        new BN(Date.now()),
         listing.category,
         listing.room_count,
         listing.bathroom_count,
         listing.guest_count,
         listing.location_value,
-        new BN(0), // total_bookings
         true, // is_active
-        new BN(listing.price)
+        new BN(listing.price),
+        { flexible: { fullRefundHours: 24 } } // cancellation_policy
       )
       .accounts({
         listingAuthority: host.publicKey,
+        userProfile: getUserProfileAddress(host.publicKey, program.programId),
         host: host_pkey,
         listing: listing_pkey,
+        availability: getAvailabilityAddress(listing_pkey, program.programId),
         systemProgram: anchor.web3.SystemProgram.programId
       })
       .signers([host])
@@ -333,6 +458,42 @@ describe("airbnb-blockhain", () => {
     console.log(`\n📊 Host final listing count: ${finalHostAccount.listingCount.toNumber()}`);
   });
 
+  it("Should reject a listing whose location is not an ISO country code", async () => {
+    const [host_pkey] = getHostAddress(host.publicKey, program.programId);
+    const hostAccount = await program.account.host.fetch(host_pkey);
+    const [listing_pkey] = getListingAddress(host.publicKey, hostAccount.listingCount.toNumber(), program.programId);
+
+    try {
+      await program.methods.initializeListing(
+        "Mountain Cabin Retreat",
+        "Cozy cabin in the mountains with fireplace and hiking trails",
+        "https://a0.muscache.com/im/pictures/prohost-api/Hosting-1194641374145248817/original/39aa64fa-38c1-4204-b6b2-8e639e43fd87.jpeg?im_w=720",
+        "Cabins",
+        2,           // room_count
+        1,           // bathroom_count
+        4,           // guest_count
+        "UK",        // location_value, the ISO code is GB
+        true,        // is_active
+        new BN(150), // price per night
+        { flexible: { fullRefundHours: 24 } },
+      )
+      .accounts({
+        listingAuthority: host.publicKey,
+        userProfile: getUserProfileAddress(host.publicKey, program.programId),
+        host: host_pkey,
+        listing: listing_pkey,
+        availability: getAvailabilityAddress(listing_pkey, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId
+      })
+      .signers([host])
+      .rpc({ commitment: "confirmed" });
+
+      assert.fail("Expected a non ISO location to fail");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidCountryCode");
+    }
+  });
+
   it("Should create a second listing and increment listing counter to 8", async () => {
     // Get the host PDA
     const [host_pkey, host_bump] = getHostAddress(
@@ -361,20 +522,21 @@ describe("airbnb-blockhain", () => {
       "Mountain Cabin Retreat",
       "Cozy cabin in the mountains with fireplace and hiking trails",
       "https://a0.muscache.com/im/pictures/prohost-api/Hosting-1194641374145248817/original/39aa64fa-38c1-4204-b6b2-8e639e43fd87.jpeg?im_w=720",
-      new BN(Date.now()),
       "Cabins",
       2,           // room_count
       1,           // bathroom_count  
       4,           // guest_count
-      "UK",        // location_value
-      new BN(0),   // total_bookings
+      "GB",        // location_value
       true,        // is_active
       new BN(150), // price per night
+      { moderate: { fullRefundHours: 120 } }, // cancellation_policy
     )
     .accounts({
       listingAuthority: host.publicKey,
+      userProfile: getUserProfileAddress(host.publicKey, program.programId),
       host: host_pkey,
       listing: listing2_pkey,
+      availability: getAvailabilityAddress(listing2_pkey, program.programId),
       systemProgram: anchor.web3.SystemProgram.programId
     })
     .signers([host])
@@ -396,79 +558,134 @@ describe("airbnb-blockhain", () => {
     // Create a guest
     await airdrop(provider.connection, guest.publicKey);
 
+    const userProfile = getUserProfileAddress(guest.publicKey, program.programId);
+    [guest_pkey, guest_bump] = getGuestAddress(
+      guest.publicKey,
+      program.programId
+    );
+
+    await initializeUserProfile(guest, "David Biagiola", "https://example.com/biagiola-profile.jpg");
+
     // Initialize guest
-    await program.methods.initializeGuest(
-      "David Biagiola",
-      "davidbiagiola5@gmail.com",
-      "https://example.com/biagiola-profile.jpg",
-      "guestpassword123",
-      new BN(Date.now()),
-      "+1234567890",
-      new BN(Date.now() - 365 * 24 * 60 * 60 * 1000), // 1 year ago (birth date)
-      "English"
-    )
-    .accounts({ guestAuthority: guest.publicKey })
+    await program.methods.initializeGuest()
+    .accounts({
+      guestAuthority: guest.publicKey,
+      userProfile: userProfile,
+      guest: guest_pkey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
     .signers([guest])
     .rpc({ commitment: "confirmed" });
 
     console.log("Guest initialized successfully!");
 
     // Fetch and verify the created guest
-    [guest_pkey, guest_bump] = getGuestAddress(
-      guest.publicKey,
-      program.programId
-    );
-
     const guestAccount = await program.account.guest.fetch(guest_pkey);
+    const profileAccount = await program.account.userProfile.fetch(userProfile);
     console.log("guest_pkey: ", guest_pkey);
     console.log("guestAccount", guestAccount);
 
     // TEST: Verify guest was created correctly
-    assert.strictEqual(guestAccount.name, "David Biagiola", "Guest name should match");
-    assert.strictEqual(guestAccount.email, "davidbiagiola5@gmail.com", "Guest email should match");
+    assert.strictEqual(profileAccount.name, "David Biagiola", "Guest name should match");
+    assert.strictEqual(profileAccount.roles & 2, 2, "Profile should hold the guest role");
     assert.strictEqual(guestAccount.guestAuthor.toString(), guest.publicKey.toString(), "Guest authority should match");
+    assert.strictEqual(guestAccount.profile.toString(), userProfile.toString(), "Guest should point at its profile");
+    assert.strictEqual(guestAccount.reservationCount.toNumber(), 0, "Guest should start with 0 reservations");
   });
 
-  it("Should create a reservation", async () => {
-    // Create a reservation
-    const reservationId = 1; // Simple hardcoded ID for testing
-    const [reservation_pkey] = getReservationAddress(
+  // Reservation PDAs come from the guest's counter, so each test books its own nights
+  const DAY = 24 * 60 * 60;
+  let reservation_pkey: PublicKey;
+
+  function nowSeconds() {
+    return Math.floor(Date.now() / 1000);
+  }
+
+  async function createReservation(listing_pkey: PublicKey, startDate: number, nights: number, guestCount = 2) {
+    const [guest_pkey] = getGuestAddress(guest.publicKey, program.programId);
+    const guestAccount = await program.account.guest.fetch(guest_pkey);
+    const listingAccount = await program.account.listing.fetch(listing_pkey);
+
+    const [reservation] = getReservationAddress(
       guest.publicKey,
-      reservationId,
+      guestAccount.reservationCount.toNumber(),
+      program.programId
+    );
+    const bookingIndex = getBookingIndexAddress(
+      listing_pkey,
+      listingAccount.bookingCount.toNumber(),
       program.programId
     );
-
-    const startDate = new BN(Date.now());
-    const endDate = new BN(Date.now() + 7 * 24 * 60 * 60 * 1000); // 7 days later
-    const totalNights = 7;
-    const pricePerNight = new BN(299);
-    const totalPrice = new BN(299 * 7);
-
-    const [host_pkey] = getHostAddress(host.publicKey, program.programId);
-    const [listing_pkey] = getListingAddress(host.publicKey, 0, program.programId);
 
     await program.methods.initializeReservation(
-      new BN(reservationId),
-      listing_pkey,
-      host.publicKey,
-      startDate,
-      endDate,
-      2, // guest_count
-      totalNights,
-      pricePerNight,
-      totalPrice,
-      { pending: {} }, // ReservationStatus::Pending
-      new BN(Date.now()),
-      { pending: {} }, // PaymentStatus::Pending
+      new BN(startDate),
+      new BN(startDate + nights * DAY),
+      guestCount,
     )
     .accounts({
       reservationAuthority: guest.publicKey,
-      reservation: reservation_pkey,
+      userProfile: getUserProfileAddress(guest.publicKey, program.programId),
+      guest: guest_pkey,
+      listing: listing_pkey,
+      host: listingAccount.host,
+      availability: getAvailabilityAddress(listing_pkey, program.programId),
+      reservation: reservation,
+      bookingIndex: bookingIndex,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([guest])
+    .rpc({ commitment: "confirmed" });
+
+    return reservation;
+  }
+
+  async function createPaymentEscrow(reservation: PublicKey, escrowId: number, releaseDate: BN) {
+    const guestTokenAccount = getAssociatedTokenAddressSync(
+      mint.publicKey,
+      guest.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const [escrow_pkey] = getPaymentEscrowAddress(
+      reservation,
+      escrowId,
+      program.programId
+    );
+
+    // The escrow must cover the price the program computed for the reservation
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+
+    await program.methods.initializePaymentEscrow(
+      new BN(escrowId),
+      reservationAccount.totalPrice,
+      releaseDate
+    )
+    .accounts({
+      guestAuthority: guest.publicKey,
+      reservation: reservation,
+      paymentEscrow: escrow_pkey,
+      platformConfig: getPlatformConfigAddress(program.programId),
+      mint: mint.publicKey,
+      guestTokenAccount: guestTokenAccount,
+      escrowVault: getEscrowVaultAddress(escrow_pkey, program.programId),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId
     })
     .signers([guest])
     .rpc({ commitment: "confirmed" });
 
+    return escrow_pkey;
+  }
+
+  it("Should create a reservation", async () => {
+    const [listing_pkey] = getListingAddress(host.publicKey, 0, program.programId);
+
+    // Check-in tomorrow for 7 nights, price and nights are computed on-chain
+    const startDate = nowSeconds() + DAY;
+    reservation_pkey = await createReservation(listing_pkey, startDate, 7);
+
     // Fetch and verify the created reservation
     const reservationAccount = await program.account.reservation.fetch(reservation_pkey);
 
@@ -478,6 +695,32 @@ describe("airbnb-blockhain", () => {
     assert.strictEqual(reservationAccount.host.toString(), host.publicKey.toString(), "Reservation host should match");
     assert.strictEqual(reservationAccount.guestCount, 2, "Guest count should be 2");
     assert.strictEqual(reservationAccount.totalNights, 7, "Total nights should be 7");
+    assert.strictEqual(reservationAccount.pricePerNight.toNumber(), 299, "Price per night should come from the listing");
+    assert.strictEqual(reservationAccount.totalPrice.toNumber(), 299 * 7, "Total price should be computed on-chain");
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "pending", "Reservation should start pending");
+  });
+
+  it("Should reject a reservation that starts in the past", async () => {
+    const [listing_pkey] = getListingAddress(host.publicKey, 0, program.programId);
+
+    try {
+      await createReservation(listing_pkey, nowSeconds() - DAY, 2);
+      assert.fail("Expected a reservation starting in the past to fail");
+    } catch (error) {
+      assert.include(error.toString(), "StartDateInPast");
+    }
+  });
+
+  it("Should reject a reservation overlapping booked nights", async () => {
+    const [listing_pkey] = getListingAddress(host.publicKey, 0, program.programId);
+
+    try {
+      // Overlaps the last nights of the first reservation
+      await createReservation(listing_pkey, nowSeconds() + 5 * DAY, 3);
+      assert.fail("Expected an overlapping reservation to fail");
+    } catch (error) {
+      assert.include(error.toString(), "DatesUnavailable");
+    }
   });
 
   it("Should create payment escrow", async () => {
@@ -490,8 +733,8 @@ describe("airbnb-blockhain", () => {
       ASSOCIATED_TOKEN_PROGRAM_ID
     );
 
-    // Mint enough tokens for all upcoming tests (10,000 tokens)
-    await program.methods.mintToken(new BN(10000))
+    // Mint enough tokens for all upcoming tests (20,000 tokens)
+    await program.methods.mintToken(new BN(20000))
       .accounts({
         creator: platformAuthority.publicKey,
         mint: mint.publicKey,
@@ -504,43 +747,11 @@ describe("airbnb-blockhain", () => {
       .signers([platformAuthority])
       .rpc({ commitment: "confirmed" });
 
-    // Create payment escrow
-    // Use existing guest (David Biagiola) and reservation (ID=1, 7 nights, 2093 tokens)
-    const reservationId = 1; // Existing reservation
-    const [reservation_pkey] = getReservationAddress(
-      guest.publicKey,
-      reservationId,
-      program.programId
-    );
-
-    const escrowId = 10; // Use unique ID to avoid conflicts
-    const [escrow_pkey] = getPaymentEscrowAddress(
-      reservation_pkey,
-      escrowId,
-      program.programId
-    );
-
-    const escrowAmount = new BN(1000); // Test with 1000 tokens
-    const releaseDate = new BN(Math.floor((Date.now() + 5 * 24 * 60 * 60 * 1000) / 1000)); // 5 days from now
-
-    await program.methods.initializePaymentEscrow(
-      new BN(escrowId),
-      escrowAmount,
-      releaseDate
-    )
-    .accounts({
-      guestAuthority: guest.publicKey,
-      reservation: reservation_pkey,
-      paymentEscrow: escrow_pkey,
-      mint: mint.publicKey,
-      guestTokenAccount: guestTokenAccount,
-      platformTreasury: platformTreasuryATA,
-      tokenProgram: TOKEN_2022_PROGRAM_ID,
-      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-      systemProgram: anchor.web3.SystemProgram.programId
-    })
-    .signers([guest])
-    .rpc({ commitment: "confirmed" });
+    // Pay for the 7 night reservation (2093 tokens), released a day after check-out
+    const reservationAccount = await program.account.reservation.fetch(reservation_pkey);
+    const escrowId = 10;
+    const releaseDate = reservationAccount.endDate.add(new BN(DAY));
+    const escrow_pkey = await createPaymentEscrow(reservation_pkey, escrowId, releaseDate);
 
     // Verify the escrow
     const escrowAccount = await program.account.paymentEscrow.fetch(escrow_pkey);
@@ -548,11 +759,20 @@ describe("airbnb-blockhain", () => {
     // TEST: Verify escrow was created correctly
     assert.strictEqual(escrowAccount.reservation.toString(), reservation_pkey.toString(), "Escrow should link to existing reservation");
     assert.strictEqual(escrowAccount.guest.toString(), guest.publicKey.toString(), "Escrow guest should match existing guest");
-    assert.strictEqual(escrowAccount.amount.toString(), "1000", "Escrow amount should be 1000");
+    assert.strictEqual(escrowAccount.amount.toString(), "2093", "Escrow amount should be the reservation total");
     
-    const expectedFee = (1000 * 500) / 10000; // 50 tokens (5% of 1000)
-    assert.strictEqual(escrowAccount.platformFee.toString(), expectedFee.toString(), "Platform fee should be 50 (5%)");
+    const expectedFee = Math.floor((2093 * 500) / 10000); // 104 tokens (5% of 2093)
+    assert.strictEqual(escrowAccount.platformFee.toString(), expectedFee.toString(), "Platform fee should be 104 (5%)");
     assert.strictEqual(Object.keys(escrowAccount.status)[0], "funded", "Escrow status should be Funded");
+
+    // TEST: The vault holds exactly the escrowed amount
+    const vaultBalance = await provider.connection.getTokenAccountBalance(getEscrowVaultAddress(escrow_pkey, program.programId));
+    assert.strictEqual(vaultBalance.value.amount, "2093", "Vault should hold the escrowed amount");
+
+    // TEST: The escrow is linked back into the reservation
+    const paidReservation = await program.account.reservation.fetch(reservation_pkey);
+    assert.strictEqual(paidReservation.paymentEscrow.toString(), escrow_pkey.toString(), "Reservation should link to the escrow");
+    assert.strictEqual(Object.keys(paidReservation.paymentStatus)[0], "paid", "Reservation should be paid");
   });
 
   it("Should fail to create escrow with unauthorized guest", async () => {
@@ -581,34 +801,29 @@ describe("airbnb-blockhain", () => {
     .signers([platformAuthority])
     .rpc({ commitment: "confirmed" });
 
-    const reservationId = 1; // Existing reservation
-    const [reservation_pkey] = getReservationAddress(
-      guest.publicKey,
-      reservationId,
-      program.programId
-    );
-
     const escrowId = 11; // Unique ID
     const [escrow_pkey] = getPaymentEscrowAddress(
       reservation_pkey,
       escrowId,
       program.programId
     );
+    const reservationAccount = await program.account.reservation.fetch(reservation_pkey);
 
     try {
       // Try to create escrow with host as authority (should fail)
       await program.methods.initializePaymentEscrow(
         new BN(escrowId),
-        new BN(500),
-        new BN(Math.floor((Date.now() + 24 * 60 * 60 * 1000) / 1000))
+        reservationAccount.totalPrice,
+        reservationAccount.endDate
       )
       .accounts({
         guestAuthority: host.publicKey, // WRONG! Should be guest.publicKey
         reservation: reservation_pkey,
         paymentEscrow: escrow_pkey,
+        platformConfig: getPlatformConfigAddress(program.programId),
         mint: mint.publicKey,
         guestTokenAccount: hostTokenAccount, // Host's token account (wrong)
-        platformTreasury: platformTreasuryATA,
+        escrowVault: getEscrowVaultAddress(escrow_pkey, program.programId),
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -619,60 +834,39 @@ describe("airbnb-blockhain", () => {
       assert.fail("Expected transaction to fail with unauthorized guest");
     } catch (error) {
       console.log("Correctly failed with unauthorized guest - only the guest who made the reservation can create escrow");
-      // Test pawsses when transaction fails due to constraint violation
+      assert.include(error.toString(), "UnauthorizedGuest");
     }
   });
 
-  it("Should test platform fee calculation", async () => {
-    const reservationId = 1; // Existing reservation
-    const [reservation_pkey] = getReservationAddress(
-      guest.publicKey,
-      reservationId,
-      program.programId
-    );
+  it("Should refuse a second escrow for a paid reservation", async () => {
+    const reservationAccount = await program.account.reservation.fetch(reservation_pkey);
 
-    const guestTokenAccount = getAssociatedTokenAddressSync(
-      mint.publicKey,
-      guest.publicKey,
-      false,
-      TOKEN_2022_PROGRAM_ID,
-      ASSOCIATED_TOKEN_PROGRAM_ID
-    );
+    try {
+      await createPaymentEscrow(reservation_pkey, 12, reservationAccount.endDate);
+      assert.fail("Expected a second escrow to fail");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowAlreadyActive");
+    }
+  });
+
+  it("Should test platform fee calculation", async () => {
+    const [listing_pkey] = getListingAddress(host.publicKey, 0, program.programId);
 
-    // Test different amounts to verify 5% fee calculation
+    // One reservation per case, each escrow must match its reservation's total
     const testCases = [
-      { amount: 100, expectedFee: 5 },   // 100 * 500 / 10000 = 5
-      { amount: 1000, expectedFee: 50 }, // 1000 * 500 / 10000 = 50
-      { amount: 450, expectedFee: 22 },  // 450 * 500 / 10000 = 22.5 → 22 (integer division)
+      { nights: 1, expectedFee: 14 }, // 299 * 500 / 10000 = 14.95 → 14 (integer division)
+      { nights: 2, expectedFee: 29 }, // 598 * 500 / 10000 = 29.9 → 29
+      { nights: 4, expectedFee: 59 }, // 1196 * 500 / 10000 = 59.8 → 59
     ];
 
+    let startDate = nowSeconds() + 10 * DAY;
     for (let i = 0; i < testCases.length; i++) {
       const testCase = testCases[i];
-      const escrowId = 20 + i; // Unique IDs to avoid conflicts
-      const [escrow_pkey] = getPaymentEscrowAddress(
-        reservation_pkey,
-        escrowId,
-        program.programId
-      );
+      const reservation = await createReservation(listing_pkey, startDate, testCase.nights);
+      startDate += (testCase.nights + 1) * DAY;
 
-      await program.methods.initializePaymentEscrow(
-        new BN(escrowId),
-        new BN(testCase.amount),
-        new BN(Math.floor((Date.now() + 24 * 60 * 60 * 1000) / 1000)) // 1 day from now
-      )
-      .accounts({
-        guestAuthority: guest.publicKey,
-        reservation: reservation_pkey,
-        paymentEscrow: escrow_pkey,
-        mint: mint.publicKey,
-        guestTokenAccount: guestTokenAccount,
-        platformTreasury: platformTreasuryATA,
-        tokenProgram: TOKEN_2022_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: anchor.web3.SystemProgram.programId
-      })
-      .signers([guest])
-      .rpc({ commitment: "confirmed" });
+      const reservationAccount = await program.account.reservation.fetch(reservation);
+      const escrow_pkey = await createPaymentEscrow(reservation, 20 + i, reservationAccount.endDate);
 
       const escrowAccount = await program.account.paymentEscrow.fetch(escrow_pkey);
       
@@ -680,256 +874,2246 @@ describe("airbnb-blockhain", () => {
       assert.strictEqual(
         escrowAccount.platformFee.toString(), 
         testCase.expectedFee.toString(), 
-        `5% fee should be ${testCase.expectedFee} for amount ${testCase.amount}`
+        `5% fee should be ${testCase.expectedFee} for ${testCase.nights} nights`
       );
     }
 
-    // Guest balance: 10000 - 1000 - 100 - 1000 - 450 = ~7450 tokens remaining
-    console.log("Platform fee calculation tests completed - guest balance: ~7450 tokens remaining");
+    // Guest balance: 20000 - 2093 - 299 - 598 - 1196 = ~15814 tokens remaining
+    console.log("Platform fee calculation tests completed - guest balance: ~15814 tokens remaining");
   });
 
-  it("Should test time-based release date scenarios", async () => {
-    // REUSE existing guest and reservation for time testing
-    console.log("🔄 Testing time-based release dates using existing infrastructure...");
-    
-    const reservationId = 1; // Existing reservation
-    const [reservation_pkey] = getReservationAddress(
-      guest.publicKey,
-      reservationId,
-      program.programId
-    );
+  it("Should test release date scenarios", async () => {
+    const [listing_pkey] = getListingAddress(host.publicKey, 0, program.programId);
+    const reservation = await createReservation(listing_pkey, nowSeconds() + 25 * DAY, 2);
+    const reservationAccount = await program.account.reservation.fetch(reservation);
 
-    const guestTokenAccount = getAssociatedTokenAddressSync(
-      mint.publicKey,
-      guest.publicKey,
-      false,
-      TOKEN_2022_PROGRAM_ID,
-      ASSOCIATED_TOKEN_PROGRAM_ID
-    );
+    // Test Case 1: Release date before check-out is rejected, the host is paid after the stay
+    try {
+      await createPaymentEscrow(reservation, 30, reservationAccount.endDate.sub(new BN(1)));
+      assert.fail("Expected a release date before check-out to fail");
+    } catch (error) {
+      assert.include(error.toString(), "ReleaseBeforeCheckOut");
+    }
 
-    // Test Case 1: Release date in the past (immediate release eligibility)
-    const escrowId1 = 30;
-    const [escrow1_pkey] = getPaymentEscrowAddress(
-      reservation_pkey,
-      escrowId1,
-      program.programId
-    );
+    // Test Case 2: Release date a week after check-out
+    const futureReleaseDate = reservationAccount.endDate.add(new BN(7 * DAY));
+    const escrow_pkey = await createPaymentEscrow(reservation, 31, futureReleaseDate);
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow_pkey);
 
-    const pastReleaseDate = new BN(Math.floor((Date.now() - 24 * 60 * 60 * 1000) / 1000)); // 1 day ago
+    console.log("Future release escrow:", {
+      releaseDate: new Date(escrowAccount.releaseDate.toNumber() * 1000).toISOString(),
+      daysUntilRelease: Math.round((escrowAccount.releaseDate.toNumber() * 1000 - Date.now()) / (24 * 60 * 60 * 1000))
+    });
 
-    await program.methods.initializePaymentEscrow(
-      new BN(escrowId1),
-      new BN(300),
-      pastReleaseDate
-    )
-    .accounts({
-      guestAuthority: guest.publicKey,
-      reservation: reservation_pkey,
-      paymentEscrow: escrow1_pkey,
-      mint: mint.publicKey,
-      guestTokenAccount: guestTokenAccount,
-      platformTreasury: platformTreasuryATA,
+    // ✅ TEST: Verify release dates match what we set
+    assert.strictEqual(escrowAccount.releaseDate.toString(), futureReleaseDate.toString(), "Release date should match");
+
+    // Guest balance: 15814 - 598 = ~15216 tokens remaining
+    console.log("✅ Release date tests completed - guest balance: ~15216 tokens remaining");
+  });
+
+  it("Should test PDA generation consistency", async () => {
+    // Test that PDA generation is deterministic and consistent
+    const testReservation = anchor.web3.Keypair.generate().publicKey;
+    const testEscrowId = 999;
+
+    // Generate PDA multiple times
+    const [pda1] = getPaymentEscrowAddress(testReservation, testEscrowId, program.programId);
+    const [pda2] = getPaymentEscrowAddress(testReservation, testEscrowId, program.programId);
+    const [pda3] = getPaymentEscrowAddress(testReservation, testEscrowId, program.programId);
+
+    // ✅ TEST: PDAs should be identical
+    assert.strictEqual(pda1.toString(), pda2.toString(), "PDA generation should be deterministic");
+    assert.strictEqual(pda2.toString(), pda3.toString(), "PDA generation should be consistent");
+
+    // ✅ TEST: Different escrow IDs should generate different PDAs
+    const [differentPda] = getPaymentEscrowAddress(testReservation, testEscrowId + 1, program.programId);
+    assert.notStrictEqual(pda1.toString(), differentPda.toString(), "Different escrow IDs should generate different PDAs");
+
+    // ✅ TEST: Different reservations should generate different PDAs
+    const [differentReservationPda] = getPaymentEscrowAddress(
+      anchor.web3.Keypair.generate().publicKey,
+      testEscrowId,
+      program.programId
+    );
+    assert.notStrictEqual(pda1.toString(), differentReservationPda.toString(), "Different reservations should generate different PDAs");
+
+    console.log("✅ PDA generation consistency tests passed!");
+    console.log("Sample PDA:", pda1.toString());
+  });
+
+  it("Should hold the payment until the guest checks in", async () => {
+    console.log("🎯 Testing payment cycle: Guest books → Guest pays → Release waits for the stay");
+    
+    const [listing_pkey] = getListingAddress(host.publicKey, 0, program.programId);
+    const reservation = await createReservation(listing_pkey, nowSeconds() + 30 * DAY, 2);
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+
+    // STEP 1: Pay the reservation total into the escrow vault
+    const escrowId = 100;
+    const escrow_pkey = await createPaymentEscrow(reservation, escrowId, reservationAccount.endDate);
+
+    console.log("💰 STEP 1: Payment escrow created (598 tokens)");
+
+    // STEP 2: Releasing before the guest has checked in must fail
+    const hostTokenAccount = getAssociatedTokenAddressSync(
+      mint.publicKey,
+      host.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+
+    try {
+      await program.methods.releasePaymentEscrow()
+      .accounts({
+        platformAuthority: platformAuthority.publicKey,
+        platformConfig: getPlatformConfigAddress(program.programId),
+        releasePaymentEscrow: escrow_pkey,
+        reservation: reservation,
+        host: getHostAddress(host.publicKey, program.programId)[0],
+        escrowVault: getEscrowVaultAddress(escrow_pkey, program.programId),
+        mint: mint.publicKey,
+        platformTreasury: platformTreasuryATA,
+        hostTokenAccount: hostTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([platformAuthority])
+      .rpc({ commitment: "confirmed" });
+
+      assert.fail("Expected the release to wait for check-in");
+    } catch (error) {
+      assert.include(error.toString(), "StayNotStarted");
+    }
+
+    console.log("🏠 STEP 2: Release refused before check-in");
+
+    // STEP 3: The funds stay in the vault
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow_pkey);
+    const vaultBalance = await provider.connection.getTokenAccountBalance(getEscrowVaultAddress(escrow_pkey, program.programId));
+
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "funded", "Escrow should still be Funded");
+    assert.strictEqual(vaultBalance.value.amount, "598", "Vault should still hold the payment");
+
+    // Guest final balance: 15216 - 598 = ~14618 tokens remaining
+    console.log("✅ Guest balance: ~14618 tokens remaining after the payment cycle");
+  });
+});
+
+// Stays only settle once their dates have passed, so the lifecycle runs against an
+// in-process bank whose clock the tests move forward
+describe("reservation lifecycle", () => {
+  const DAY = 24 * 60 * 60;
+  const PRICE = 1000;               // per night
+  const NIGHTS = 2;
+  const TOTAL = PRICE * NIGHTS;
+  const FEE = (TOTAL * 500) / 10000; // 5% platform fee
+  const HOST_AMOUNT = TOTAL - FEE;
+
+  const host = Keypair.generate();
+  const guest = Keypair.generate();
+  const platformAuthority = Keypair.generate(); // Mint creator and release authority
+  const arbiter = Keypair.generate();
+  const stranger = Keypair.generate();
+  const retiringHost = Keypair.generate();  // Closes everything it opened
+  const retiringGuest = Keypair.generate();
+  const cancellingHost = Keypair.generate(); // Keeps its penalty away from the main host
+  const brokeGuest = Keypair.generate();     // Holds no tokens, every payment fails
+  const legacyUser = Keypair.generate();     // Host, guest and listing written in the pre-profile layouts
+  const editingHost = Keypair.generate();    // Owns the profile and listing the update tests rewrite
+  let mint = Keypair.generate();     // Swapped out by the transfer fee tests
+
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AirbnbBlockhain>;
+  let listing: PublicKey;
+  let platformTreasury: PublicKey;
+  let nextStartDay = 0;
+  let nextEscrowId = 1;
+
+  before(async () => {
+    // Anchor.toml's localnet id is not the declared one, so load the program at its declared id
+    context = await startAnchor(
+      "",
+      [{ name: "airbnb_blockhain", programId: new PublicKey(IDL.address) }],
+      [host, guest, platformAuthority, arbiter, stranger, retiringHost, retiringGuest, cancellingHost, brokeGuest, legacyUser, editingHost].map(keypair => ({
+        address: keypair.publicKey,
+        info: {
+          lamports: 10 * anchor.web3.LAMPORTS_PER_SOL,
+          data: Buffer.alloc(0),
+          owner: anchor.web3.SystemProgram.programId,
+          executable: false,
+        },
+      }))
+    );
+    provider = new BankrunProvider(context);
+    program = new Program<AirbnbBlockhain>(IDL as AirbnbBlockhain, provider);
+
+    // Start at midnight so stays line up with whole days
+    await warpTo(Math.ceil((await now()) / DAY) * DAY);
+
+    // No transfer fee, so every balance can be checked exactly
+    await program.methods.initializeToken(0, new BN(0))
+    .accounts({
+      creator: platformAuthority.publicKey,
+      mint: mint.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([platformAuthority, mint])
+    .rpc();
+
+    platformTreasury = await createTokenAccount(platformAuthority.publicKey);
+    await createTokenAccount(host.publicKey);
+    await mintTo(guest.publicKey, 1_000_000);
+
+    // initialize_platform checks the upgradeable loader's program data, so write the config directly
+    const [platformConfig, bump] = PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode(PLATFORM_CONFIG_SEED)],
+      program.programId
+    );
+    const data = await program.coder.accounts.encode("PlatformConfig", {
+      admin: provider.wallet.publicKey,
+      feeBps: 500,
+      acceptedMint: mint.publicKey,
+      mintDecimals: 9,
+      treasury: platformTreasury,
+      releaseAuthority: platformAuthority.publicKey,
+      arbiter: arbiter.publicKey,
+      refundFeePolicy: { retainPlatformFee: {} },
+      hostCancelPenaltyBps: 1000, // 10% of the total price
+      crankBountyBps: 1000,       // 10% of the platform fee
+      openEscrowCount: new BN(0),
+      updatedAt: new BN(0),
+      bump,
+    });
+    context.setAccount(platformConfig, {
+      lamports: anchor.web3.LAMPORTS_PER_SOL,
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+
+    listing = await setupHost(host);
+    await setupGuest(guest);
+  });
+
+  async function now() {
+    const clock = await context.banksClient.getClock();
+    return Number(clock.unixTimestamp);
+  }
+
+  async function warpTo(unixTimestamp: number) {
+    const clock = await context.banksClient.getClock();
+    // A new slot also gives a new blockhash, so a retried instruction is a new transaction
+    const slot = clock.slot + BigInt(1);
+    context.warpToSlot(slot);
+    context.setClock(new Clock(slot, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, BigInt(unixTimestamp)));
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+    } catch (error) {
+      const logs = (error.logs ?? []).join("\n");
+      assert.include(`${error.error?.errorCode?.code} ${error} ${logs}`, code);
+      return;
+    }
+    assert.fail(`Expected the transaction to fail with ${code}`);
+  }
+
+  function tokenAccount(owner: PublicKey) {
+    return getAssociatedTokenAddressSync(
+      mint.publicKey,
+      owner,
+      false,
+      TOKEN_2022_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+  }
+
+  // Token amount, stored as a little-endian u64 after the mint and owner
+  async function balance(account: PublicKey) {
+    const info = await context.banksClient.getAccount(account);
+    return Number(Buffer.from(info.data).readBigUInt64LE(64));
+  }
+
+  async function createTokenAccount(owner: PublicKey, tokenMint = mint.publicKey) {
+    const account = getAssociatedTokenAddressSync(
+      tokenMint,
+      owner,
+      false,
+      TOKEN_2022_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await provider.sendAndConfirm(new Transaction().add(
+      createAssociatedTokenAccountIdempotentInstruction(
+        provider.wallet.publicKey,
+        account,
+        owner,
+        tokenMint,
+        TOKEN_2022_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      )
+    ));
+    return account;
+  }
+
+  async function mintTo(recipient: PublicKey, amount: number) {
+    await program.methods.mintToken(new BN(amount))
+    .accounts({
+      creator: platformAuthority.publicKey,
+      mint: mint.publicKey,
+      recipient: recipient,
+      recipientAta: tokenAccount(recipient),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([platformAuthority])
+    .rpc();
+  }
+
+  async function initializeUserProfile(user: Keypair) {
+    await program.methods.initializeUserProfile(
+      "Lifecycle User",
+      "",
+      Array.from(Keypair.generate().publicKey.toBytes()),
+      `ipfs://profiles/${user.publicKey.toBase58()}`,
+      "en",
+    )
+    .accounts({
+      authority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+  }
+
+  // Profile, host role and a first listing, returns the listing
+  async function setupHost(user: Keypair) {
+    const [host_pkey] = getHostAddress(user.publicKey, program.programId);
+    const [listing_pkey] = getListingAddress(user.publicKey, 0, program.programId);
+
+    await initializeUserProfile(user);
+    await program.methods.initializeHost()
+    .accounts({
+      hostAuthority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+      host: host_pkey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+
+    await program.methods.initializeListing(
+      "Lifecycle Cabin",
+      "Cabin booked by the lifecycle tests",
+      "https://example.com/cabin.jpg",
+      "Cabins",
+      2, // room_count
+      1, // bathroom_count
+      4, // guest_count
+      "FR",
+      true,
+      new BN(PRICE),
+      { flexible: { fullRefundHours: 24 } },
+    )
+    .accounts({
+      listingAuthority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+      host: host_pkey,
+      listing: listing_pkey,
+      availability: getAvailabilityAddress(listing_pkey, program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+
+    return listing_pkey;
+  }
+
+  async function setupGuest(user: Keypair) {
+    await initializeUserProfile(user);
+    await program.methods.initializeGuest()
+    .accounts({
+      guestAuthority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+      guest: getGuestAddress(user.publicKey, program.programId)[0],
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+  }
+
+  // Book the next free nights at least `daysAhead` days from now, bookings never overlap
+  async function book(listing_pkey = listing, daysAhead = 1, nights = NIGHTS) {
+    const today = Math.floor((await now()) / DAY);
+    const startDay = Math.max(nextStartDay, today + daysAhead);
+    nextStartDay = startDay + nights;
+
+    const [guest_pkey] = getGuestAddress(guest.publicKey, program.programId);
+    const guestAccount = await program.account.guest.fetch(guest_pkey);
+    const listingAccount = await program.account.listing.fetch(listing_pkey);
+    const [reservation] = getReservationAddress(
+      guest.publicKey,
+      guestAccount.reservationCount.toNumber(),
+      program.programId
+    );
+
+    await program.methods.initializeReservation(
+      new BN(startDay * DAY),
+      new BN((startDay + nights) * DAY),
+      2,
+    )
+    .accounts({
+      reservationAuthority: guest.publicKey,
+      userProfile: getUserProfileAddress(guest.publicKey, program.programId),
+      guest: guest_pkey,
+      listing: listing_pkey,
+      host: listingAccount.host,
+      availability: getAvailabilityAddress(listing_pkey, program.programId),
+      reservation: reservation,
+      bookingIndex: getBookingIndexAddress(listing_pkey, listingAccount.bookingCount.toNumber(), program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([guest])
+    .rpc();
+
+    return reservation;
+  }
+
+  // Fund an escrow for the reservation's total, released at check-out
+  async function pay(reservation: PublicKey) {
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    const escrowId = nextEscrowId++;
+    const [escrow] = getPaymentEscrowAddress(reservation, escrowId, program.programId);
+
+    await program.methods.initializePaymentEscrow(
+      new BN(escrowId),
+      reservationAccount.totalPrice,
+      reservationAccount.endDate
+    )
+    .accounts({
+      guestAuthority: guest.publicKey,
+      reservation: reservation,
+      paymentEscrow: escrow,
+      platformConfig: getPlatformConfigAddress(program.programId),
+      mint: mint.publicKey,
+      guestTokenAccount: tokenAccount(guest.publicKey),
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([guest])
+    .rpc();
+
+    return escrow;
+  }
+
+  async function confirm(reservation: PublicKey, signer = host) {
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await program.methods.confirmReservation()
+    .accounts({
+      hostAuthority: signer.publicKey,
+      host: getHostAddress(reservationAccount.host, program.programId)[0],
+      listing: reservationAccount.listing,
+      reservation: reservation,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  async function checkIn(reservation: PublicKey, signer = guest) {
+    await program.methods.checkIn()
+    .accounts({
+      authority: signer.publicKey,
+      reservation: reservation,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  // Pay, confirm and check in on the first night, returns the escrow
+  async function startStay(reservation: PublicKey) {
+    const escrow = await pay(reservation);
+    await confirm(reservation);
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await warpTo(reservationAccount.startDate.toNumber());
+    await checkIn(reservation);
+    return escrow;
+  }
+
+  async function release(escrow: PublicKey, signer = platformAuthority) {
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    await program.methods.releasePaymentEscrow()
+    .accounts({
+      platformAuthority: signer.publicKey,
+      platformConfig: getPlatformConfigAddress(program.programId),
+      releasePaymentEscrow: escrow,
+      reservation: escrowAccount.reservation,
+      host: getHostAddress(escrowAccount.host, program.programId)[0],
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
+      mint: mint.publicKey,
+      platformTreasury: platformTreasury,
+      hostTokenAccount: tokenAccount(escrowAccount.host),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  async function refund(escrow: PublicKey, signer = platformAuthority) {
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    await program.methods.refundPaymentEscrow()
+    .accounts({
+      platformAuthority: signer.publicKey,
+      platformConfig: getPlatformConfigAddress(program.programId),
+      paymentEscrow: escrow,
+      reservation: escrowAccount.reservation,
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
+      mint: mint.publicKey,
+      platformTreasury: platformTreasury,
+      guestTokenAccount: tokenAccount(escrowAccount.guest),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  async function openDispute(escrow: PublicKey, signer = guest) {
+    await program.methods.openDispute(
+      Array.from(Buffer.alloc(32, 1)),
+      ["ipfs://evidence"],
+    )
+    .accounts({
+      disputeAuthority: signer.publicKey,
+      paymentEscrow: escrow,
+      dispute: getDisputeAddress(escrow, program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  async function resolveDispute(escrow: PublicKey, guestShareBps: number, signer = arbiter) {
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    await program.methods.resolveDispute(guestShareBps)
+    .accounts({
+      arbiter: signer.publicKey,
+      platformConfig: getPlatformConfigAddress(program.programId),
+      dispute: getDisputeAddress(escrow, program.programId),
+      paymentEscrow: escrow,
+      reservation: escrowAccount.reservation,
+      host: getHostAddress(escrowAccount.host, program.programId)[0],
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
+      mint: mint.publicKey,
+      platformTreasury: platformTreasury,
+      guestTokenAccount: tokenAccount(escrowAccount.guest),
+      hostTokenAccount: tokenAccount(escrowAccount.host),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  // Accounts for a host ending a reservation, with the guest's escrow refunded if there is one
+  async function hostRefundAccounts(reservation: PublicKey, signer: Keypair) {
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    const escrow = reservationAccount.paymentEscrow;
+    return {
+      hostAuthority: signer.publicKey,
+      host: getHostAddress(reservationAccount.host, program.programId)[0],
+      listing: reservationAccount.listing,
+      reservation: reservation,
+      availability: getAvailabilityAddress(reservationAccount.listing, program.programId),
+      platformConfig: getPlatformConfigAddress(program.programId),
+      paymentEscrow: escrow,
+      escrowVault: escrow ? getEscrowVaultAddress(escrow, program.programId) : null,
+      mint: escrow ? mint.publicKey : null,
+      guestTokenAccount: escrow ? tokenAccount(reservationAccount.guest) : null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    };
+  }
+
+  async function decline(reservation: PublicKey, signer = host) {
+    await program.methods.declineReservation()
+    .accounts(await hostRefundAccounts(reservation, signer))
+    .signers([signer])
+    .rpc();
+  }
+
+  async function closeReservation(reservation: PublicKey) {
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await program.methods.closeReservation()
+    .accounts({
+      reservationAuthority: guest.publicKey,
+      reservation: reservation,
+      guest: getGuestAddress(guest.publicKey, program.programId)[0],
+      listing: reservationAccount.listing,
+      bookingIndex: getBookingIndexAddress(reservationAccount.listing, reservationAccount.bookingIndex.toNumber(), program.programId),
+      paymentEscrow: reservationAccount.paymentEscrow,
+    })
+    .signers([guest])
+    .rpc();
+  }
+
+  // A resolved dispute is closed along with its escrow unless `withDispute` is false
+  async function closePaymentEscrow(escrow: PublicKey, withDispute = true) {
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    const dispute = withDispute ? escrowAccount.dispute : null;
+    await program.methods.closePaymentEscrow()
+    .accounts({
+      guestAuthority: guest.publicKey,
+      paymentEscrow: escrow,
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
+      mint: mint.publicKey,
+      dispute: dispute,
+      disputeOpener: dispute ? (await program.account.dispute.fetch(dispute)).openedBy : null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([guest])
+    .rpc();
+  }
+
+  async function closeListing(user: Keypair, listing_pkey: PublicKey) {
+    await program.methods.closeListing()
+    .accounts({
+      listingAuthority: user.publicKey,
+      host: getHostAddress(user.publicKey, program.programId)[0],
+      listing: listing_pkey,
+      availability: getAvailabilityAddress(listing_pkey, program.programId),
+    })
+    .signers([user])
+    .rpc();
+  }
+
+  async function closeHost(user: Keypair) {
+    await program.methods.closeHost()
+    .accounts({
+      hostAuthority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+      host: getHostAddress(user.publicKey, program.programId)[0],
+    })
+    .signers([user])
+    .rpc();
+  }
+
+  async function closeGuest(user: Keypair) {
+    await program.methods.closeGuest()
+    .accounts({
+      guestAuthority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+      guest: getGuestAddress(user.publicKey, program.programId)[0],
+    })
+    .signers([user])
+    .rpc();
+  }
+
+  async function closeUserProfile(user: Keypair) {
+    await program.methods.closeUserProfile()
+    .accounts({
+      authority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+    })
+    .signers([user])
+    .rpc();
+  }
+
+  // Cancel as the guest, the escrow accounts can be left out to check they are required
+  async function cancel(reservation: PublicKey, withEscrow = true) {
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    const escrow = withEscrow ? reservationAccount.paymentEscrow : null;
+    await program.methods.cancelReservation()
+    .accounts({
+      guestAuthority: guest.publicKey,
+      listing: reservationAccount.listing,
+      host: getHostAddress(reservationAccount.host, program.programId)[0],
+      reservation: reservation,
+      availability: getAvailabilityAddress(reservationAccount.listing, program.programId),
+      platformConfig: getPlatformConfigAddress(program.programId),
+      paymentEscrow: escrow,
+      escrowVault: escrow ? getEscrowVaultAddress(escrow, program.programId) : null,
+      mint: escrow ? mint.publicKey : null,
+      guestTokenAccount: escrow ? tokenAccount(reservationAccount.guest) : null,
+      hostTokenAccount: escrow ? tokenAccount(reservationAccount.host) : null,
+      platformTreasury: escrow ? platformTreasury : null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([guest])
+    .rpc();
+  }
+
+  async function hostCancel(reservation: PublicKey, signer = host) {
+    await program.methods.hostCancelReservation()
+    .accounts(await hostRefundAccounts(reservation, signer))
+    .signers([signer])
+    .rpc();
+  }
+
+  async function complete(reservation: PublicKey, signer = host) {
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await program.methods.completeReservation()
+    .accounts({
+      authority: signer.publicKey,
+      listing: reservationAccount.listing,
+      reservation: reservation,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  async function crank(escrow: PublicKey, cranker = stranger) {
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    await program.methods.crankReleasePaymentEscrow()
+    .accounts({
+      cranker: cranker.publicKey,
+      platformConfig: getPlatformConfigAddress(program.programId),
+      paymentEscrow: escrow,
+      reservation: escrowAccount.reservation,
+      host: getHostAddress(escrowAccount.host, program.programId)[0],
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
+      mint: mint.publicKey,
+      platformTreasury: platformTreasury,
+      hostTokenAccount: tokenAccount(escrowAccount.host),
+      crankerTokenAccount: tokenAccount(cranker.publicKey),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([cranker])
+    .rpc();
+  }
+
+  async function expire(reservation: PublicKey, cranker = stranger) {
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await program.methods.expireReservation()
+    .accounts({
+      cranker: cranker.publicKey,
+      guest: reservationAccount.guest,
+      guestAccount: getGuestAddress(reservationAccount.guest, program.programId)[0],
+      reservation: reservation,
+      bookingIndex: getBookingIndexAddress(reservationAccount.listing, reservationAccount.bookingIndex.toNumber(), program.programId),
+      listing: reservationAccount.listing,
+      availability: getAvailabilityAddress(reservationAccount.listing, program.programId),
+      platformConfig: getPlatformConfigAddress(program.programId),
+      paymentEscrow: null,
+      escrowVault: null,
+      mint: null,
+      guestTokenAccount: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([cranker])
+    .rpc();
+  }
+
+  // Update only the given listing fields, everything left out is passed as None
+  async function updateListing(fields: {
+    title?: string,
+    description?: string,
+    imageUrl?: string,
+    category?: string,
+    roomCount?: number,
+    bathroomCount?: number,
+    guestCount?: number,
+    locationValue?: string,
+    price?: BN,
+    cancellationPolicy?: object,
+  }, signer = host, listing_pkey = listing) {
+    await program.methods.updateListing(
+      fields.title ?? null,
+      fields.description ?? null,
+      fields.imageUrl ?? null,
+      fields.category ?? null,
+      fields.roomCount ?? null,
+      fields.bathroomCount ?? null,
+      fields.guestCount ?? null,
+      fields.locationValue ?? null,
+      fields.price ?? null,
+      fields.cancellationPolicy ?? null,
+    )
+    .accounts({
+      listingAuthority: signer.publicKey,
+      host: getHostAddress(signer.publicKey, program.programId)[0],
+      listing: listing_pkey,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  async function updateUserProfile(fields: {
+    name?: string,
+    imageUrl?: string,
+    profileCommitment?: number[],
+    metadataUri?: string,
+    preferredLanguage?: string,
+  }, user = editingHost) {
+    await program.methods.updateUserProfile(
+      fields.name ?? null,
+      fields.imageUrl ?? null,
+      fields.profileCommitment ?? null,
+      fields.metadataUri ?? null,
+      fields.preferredLanguage ?? null,
+    )
+    .accounts({
+      authority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+    })
+    .signers([user])
+    .rpc();
+  }
+
+  async function setListingActive(isActive: boolean, signer = host, listing_pkey = listing) {
+    await program.methods.setListingActive(isActive)
+    .accounts({
+      listingAuthority: signer.publicKey,
+      host: getHostAddress(signer.publicKey, program.programId)[0],
+      listing: listing_pkey,
+    })
+    .signers([signer])
+    .rpc();
+  }
+
+  // Reserve the next free nights and pay for them in one transaction
+  async function bookAndPay(user = guest, listing_pkey = listing, nights = NIGHTS) {
+    const today = Math.floor((await now()) / DAY);
+    const startDay = Math.max(nextStartDay, today + 1);
+    nextStartDay = startDay + nights;
+    const endDate = new BN((startDay + nights) * DAY);
+
+    const [guest_pkey] = getGuestAddress(user.publicKey, program.programId);
+    const guestAccount = await program.account.guest.fetch(guest_pkey);
+    const listingAccount = await program.account.listing.fetch(listing_pkey);
+    const [reservation] = getReservationAddress(
+      user.publicKey,
+      guestAccount.reservationCount.toNumber(),
+      program.programId
+    );
+    const escrowId = nextEscrowId++;
+    const [escrow] = getPaymentEscrowAddress(reservation, escrowId, program.programId);
+
+    await program.methods.bookListing(
+      new BN(escrowId),
+      new BN(startDay * DAY),
+      endDate,
+      2,
+      endDate,
+    )
+    .accounts({
+      guestAuthority: user.publicKey,
+      userProfile: getUserProfileAddress(user.publicKey, program.programId),
+      guest: guest_pkey,
+      listing: listing_pkey,
+      host: listingAccount.host,
+      availability: getAvailabilityAddress(listing_pkey, program.programId),
+      reservation: reservation,
+      bookingIndex: getBookingIndexAddress(listing_pkey, listingAccount.bookingCount.toNumber(), program.programId),
+      paymentEscrow: escrow,
+      platformConfig: getPlatformConfigAddress(program.programId),
+      mint: mint.publicKey,
+      guestTokenAccount: tokenAccount(user.publicKey),
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+
+    return { reservation, escrow };
+  }
+
+  // Point the platform at another mint and treasury, outstanding escrows are left as they are
+  async function useMint(acceptedMint: Keypair, treasury: PublicKey) {
+    const platformConfig = getPlatformConfigAddress(program.programId);
+    const config = await program.account.platformConfig.fetch(platformConfig);
+    const info = await context.banksClient.getAccount(platformConfig);
+    const data = await program.coder.accounts.encode("PlatformConfig", {
+      ...config,
+      acceptedMint: acceptedMint.publicKey,
+      treasury,
+    });
+    context.setAccount(platformConfig, {
+      lamports: info.lamports,
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+    mint = acceptedMint;
+    platformTreasury = treasury;
+  }
+
+  // Switch to a new mint charging a transfer fee, funds the guest, returns a function restoring the old mint
+  async function useFeeMint(feeBps: number, maxFee: number) {
+    const previousMint = mint;
+    const previousTreasury = platformTreasury;
+    const feeMint = Keypair.generate();
+    await program.methods.initializeToken(feeBps, new BN(maxFee))
+    .accounts({
+      creator: platformAuthority.publicKey,
+      mint: feeMint.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([platformAuthority, feeMint])
+    .rpc();
+
+    await useMint(feeMint, await createTokenAccount(platformAuthority.publicKey, feeMint.publicKey));
+    await createTokenAccount(host.publicKey);
+    await mintTo(guest.publicKey, 1_000_000);
+
+    return () => useMint(previousMint, previousTreasury);
+  }
+
+  // Little-endian borsh fields, for writing accounts in the pre-profile layouts
+  function borshString(value: string) {
+    const length = Buffer.alloc(4);
+    length.writeUInt32LE(Buffer.byteLength(value));
+    return Buffer.concat([length, Buffer.from(value)]);
+  }
+
+  function borshU64(value: number) {
+    const bytes = Buffer.alloc(8);
+    bytes.writeBigUInt64LE(BigInt(value));
+    return bytes;
+  }
+
+  // Write a rent-exempt account of the legacy size: discriminator, fields, then zero padding
+  async function setLegacyAccount(address: PublicKey, accountName: string, space: number, fields: Buffer[]) {
+    const discriminator = Buffer.from(IDL.accounts.find(account => account.name === accountName).discriminator);
+    const data = Buffer.alloc(8 + space);
+    Buffer.concat([discriminator, ...fields]).copy(data);
+    const rent = await context.banksClient.getRent();
+    context.setAccount(address, {
+      lamports: Number(rent.minimumBalance(BigInt(data.length))),
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+  }
+
+  async function lamports(address: PublicKey) {
+    return Number((await context.banksClient.getAccount(address)).lamports);
+  }
+
+  // A migrated account is rent-exempt at its new size and holds nothing past the new layout
+  async function assertRewritten(address: PublicKey, accountName: string, migrated: unknown) {
+    const info = await context.banksClient.getAccount(address);
+    const data = Buffer.from(info.data);
+    const rent = await context.banksClient.getRent();
+    assert.strictEqual(Number(info.lamports), Number(rent.minimumBalance(BigInt(data.length))), `${accountName} should be rent-exempt at its new size`);
+
+    const encoded = await program.coder.accounts.encode(accountName, migrated);
+    assert.isTrue(data.subarray(0, encoded.length).equals(encoded), `${accountName} should hold only the new layout`);
+    assert.isTrue(data.subarray(encoded.length).every(byte => byte === 0), `${accountName} should be zeroed past the new layout`);
+    return data;
+  }
+
+  it("Should release the escrow to the host once the stay is over", async () => {
+    const reservation = await book();
+    const escrow = await startStay(reservation);
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await warpTo(reservationAccount.endDate.toNumber());
+
+    const hostBefore = await balance(tokenAccount(host.publicKey));
+    const treasuryBefore = await balance(platformTreasury);
+    await release(escrow);
+
+    // TEST: The host gets the stay, the treasury the platform fee, the vault is emptied
+    assert.strictEqual(await balance(tokenAccount(host.publicKey)) - hostBefore, HOST_AMOUNT, "Host should receive the total less the platform fee");
+    assert.strictEqual(await balance(platformTreasury) - treasuryBefore, FEE, "Treasury should receive the platform fee");
+    assert.strictEqual(await balance(getEscrowVaultAddress(escrow, program.programId)), 0, "Vault should be empty");
+
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "released", "Escrow should be Released");
+  });
+
+  it("Should refuse to release the escrow before the release date", async () => {
+    const reservation = await book();
+    const escrow = await startStay(reservation);
+
+    // Checked in, but the release date is check-out
+    await expectError(release(escrow), "ReleaseNotYetAllowed");
+
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "funded", "Escrow should still be Funded");
+  });
+
+  it("Should refund the guest less the platform fee", async () => {
+    const reservation = await book();
+    const escrow = await pay(reservation);
+
+    const guestBefore = await balance(tokenAccount(guest.publicKey));
+    const treasuryBefore = await balance(platformTreasury);
+    await refund(escrow);
+
+    // TEST: The platform keeps its fee under RetainPlatformFee
+    assert.strictEqual(await balance(tokenAccount(guest.publicKey)) - guestBefore, TOTAL - FEE, "Guest should get the total less the platform fee");
+    assert.strictEqual(await balance(platformTreasury) - treasuryBefore, FEE, "Treasury should keep the platform fee");
+
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "refunded", "Escrow should be Refunded");
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.paymentStatus)[0], "refunded", "Reservation should be marked refunded");
+  });
+
+  it("Should refuse a refund not signed by the release authority", async () => {
+    const reservation = await book();
+    const escrow = await pay(reservation);
+
+    await expectError(refund(escrow, guest), "UnauthorizedReleaseAuthority");
+
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "funded", "Escrow should still be Funded");
+  });
+
+  it("Should freeze a disputed escrow until the arbiter splits it", async () => {
+    const reservation = await book();
+    const escrow = await startStay(reservation);
+    await openDispute(escrow);
+
+    let escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "disputed", "Escrow should be Disputed");
+
+    // TEST: A disputed escrow cannot be released, even after check-out
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await warpTo(reservationAccount.endDate.toNumber());
+    await expectError(release(escrow), "EscrowDisputed");
+
+    const guestBefore = await balance(tokenAccount(guest.publicKey));
+    const hostBefore = await balance(tokenAccount(host.publicKey));
+    const treasuryBefore = await balance(platformTreasury);
+    await resolveDispute(escrow, 5000);
+
+    // TEST: Half of the stay goes to each party, the treasury keeps the fee
+    assert.strictEqual(await balance(tokenAccount(guest.publicKey)) - guestBefore, HOST_AMOUNT / 2, "Guest should get half of the stay");
+    assert.strictEqual(await balance(tokenAccount(host.publicKey)) - hostBefore, HOST_AMOUNT / 2, "Host should get the other half");
+    assert.strictEqual(await balance(platformTreasury) - treasuryBefore, FEE, "Treasury should keep the platform fee");
+
+    escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "released", "Escrow should be Released");
+    const dispute = await program.account.dispute.fetch(getDisputeAddress(escrow, program.programId));
+    assert.strictEqual(Object.keys(dispute.status)[0], "resolved", "Dispute should be Resolved");
+    assert.strictEqual(dispute.guestShareBps, 5000);
+  });
+
+  it("Should only let the parties open and the arbiter resolve a dispute", async () => {
+    const reservation = await book();
+    const escrow = await pay(reservation);
+
+    await expectError(openDispute(escrow, stranger), "UnauthorizedParty");
+
+    await openDispute(escrow, host);
+    await expectError(resolveDispute(escrow, 5000, platformAuthority), "UnauthorizedArbiter");
+
+    const dispute = await program.account.dispute.fetch(getDisputeAddress(escrow, program.programId));
+    assert.strictEqual(Object.keys(dispute.status)[0], "open", "Dispute should still be Open");
+  });
+
+  it("Should let the host confirm a pending reservation", async () => {
+    const reservation = await book();
+    await pay(reservation);
+    await confirm(reservation);
+
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "confirmed", "Reservation should be Confirmed");
+  });
+
+  it("Should refuse a confirmation not signed by the host", async () => {
+    const reservation = await book();
+    await pay(reservation);
+
+    await expectError(confirm(reservation, guest), "UnauthorizedHost");
+
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "pending", "Reservation should still be Pending");
+  });
+
+  it("Should refund a declined reservation in full and free its dates", async () => {
+    const reservation = await book();
+    const escrow = await pay(reservation);
+
+    const guestBefore = await balance(tokenAccount(guest.publicKey));
+    await decline(reservation);
+
+    // TEST: The guest is not at fault, so the platform fee is refunded too
+    assert.strictEqual(await balance(tokenAccount(guest.publicKey)) - guestBefore, TOTAL, "Guest should get the whole payment back");
+
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "refunded", "Escrow should be Refunded");
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "cancelled", "Reservation should be Cancelled");
+
+    // TEST: The same nights can be booked again
+    nextStartDay = reservationAccount.startDate.toNumber() / DAY;
+    const rebooked = await program.account.reservation.fetch(await book());
+    assert.isTrue(rebooked.startDate.eq(reservationAccount.startDate), "Declined nights should be bookable again");
+  });
+
+  it("Should refuse to decline a confirmed reservation", async () => {
+    const reservation = await book();
+    await pay(reservation);
+    await confirm(reservation);
+
+    await expectError(decline(reservation), "ReservationNotPending");
+  });
+
+  it("Should close a settled reservation and then its escrow", async () => {
+    const reservation = await book();
+    const escrow = await pay(reservation);
+    await decline(reservation);
+
+    const [guest_pkey] = getGuestAddress(guest.publicKey, program.programId);
+    const openBefore = (await program.account.guest.fetch(guest_pkey)).openReservationCount.toNumber();
+    const listingOpenBefore = (await program.account.listing.fetch(listing)).openReservationCount.toNumber();
+
+    // The reservation checks the escrow is settled, so it is closed first
+    await closeReservation(reservation);
+    await closePaymentEscrow(escrow);
+
+    assert.isNull(await program.account.reservation.fetchNullable(reservation), "Reservation should be closed");
+    assert.isNull(await program.account.paymentEscrow.fetchNullable(escrow), "Escrow should be closed");
+    assert.strictEqual((await program.account.guest.fetch(guest_pkey)).openReservationCount.toNumber(), openBefore - 1, "Guest open reservations should drop");
+    assert.strictEqual((await program.account.listing.fetch(listing)).openReservationCount.toNumber(), listingOpenBefore - 1, "Listing open reservations should drop");
+  });
+
+  it("Should refuse to close anything still in use", async () => {
+    const reservation = await book();
+    await pay(reservation);
+
+    // TEST: An open reservation keeps itself, its listing, guest, host and profiles alive
+    await expectError(closeReservation(reservation), "ReservationNotFinished");
+    await expectError(closeListing(host, listing), "ListingHasOpenReservations");
+    await expectError(closeGuest(guest), "GuestHasOpenReservations");
+    await expectError(closeHost(host), "HostHasActiveListings");
+    await expectError(closeUserProfile(host), "ProfileHasRoles");
+  });
+
+  it("Should let a host and a guest with nothing open close their accounts", async () => {
+    const retiringListing = await setupHost(retiringHost);
+    await setupGuest(retiringGuest);
+
+    await closeListing(retiringHost, retiringListing);
+    await closeHost(retiringHost);
+    await closeUserProfile(retiringHost);
+    await closeGuest(retiringGuest);
+    await closeUserProfile(retiringGuest);
+
+    assert.isNull(await program.account.listing.fetchNullable(retiringListing), "Listing should be closed");
+    assert.isNull(await program.account.host.fetchNullable(getHostAddress(retiringHost.publicKey, program.programId)[0]), "Host should be closed");
+    assert.isNull(await program.account.guest.fetchNullable(getGuestAddress(retiringGuest.publicKey, program.programId)[0]), "Guest should be closed");
+    assert.isNull(await program.account.userProfile.fetchNullable(getUserProfileAddress(retiringHost.publicKey, program.programId)), "Host profile should be closed");
+    assert.isNull(await program.account.userProfile.fetchNullable(getUserProfileAddress(retiringGuest.publicKey, program.programId)), "Guest profile should be closed");
+  });
+
+  it("Should refund the stay when the guest cancels before the host confirms", async () => {
+    const reservation = await book();
+    const escrow = await pay(reservation);
+
+    const guestBefore = await balance(tokenAccount(guest.publicKey));
+    const treasuryBefore = await balance(platformTreasury);
+    await cancel(reservation);
+
+    // TEST: The whole stay comes back, the platform keeps its fee under RetainPlatformFee
+    assert.strictEqual(await balance(tokenAccount(guest.publicKey)) - guestBefore, HOST_AMOUNT, "Guest should get the stay back");
+    assert.strictEqual(await balance(platformTreasury) - treasuryBefore, FEE, "Treasury should keep the platform fee");
+
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "refunded", "Escrow should be Refunded");
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "cancelled", "Reservation should be Cancelled");
+  });
+
+  it("Should apply the listing's policy when a confirmed stay is cancelled late", async () => {
+    const reservation = await book(listing, 2);
+    const escrow = await pay(reservation);
+    await confirm(reservation);
+
+    // Twelve hours before check-in, inside the flexible policy's 24 hours
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await warpTo(reservationAccount.startDate.toNumber() - 12 * 60 * 60);
+
+    const guestBefore = await balance(tokenAccount(guest.publicKey));
+    const hostBefore = await balance(tokenAccount(host.publicKey));
+    const treasuryBefore = await balance(platformTreasury);
+    await cancel(reservation);
+
+    // TEST: Nothing comes back to the guest, the host is paid as for a completed stay
+    assert.strictEqual(await balance(tokenAccount(guest.publicKey)) - guestBefore, 0, "Guest should get nothing back");
+    assert.strictEqual(await balance(tokenAccount(host.publicKey)) - hostBefore, HOST_AMOUNT, "Host should get the stay");
+    assert.strictEqual(await balance(platformTreasury) - treasuryBefore, FEE, "Treasury should get the platform fee");
+
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "released", "Escrow should be Released");
+  });
+
+  it("Should refuse to cancel a paid reservation without its escrow", async () => {
+    const reservation = await book();
+    await pay(reservation);
+
+    await expectError(cancel(reservation, false), "MissingEscrowAccounts");
+
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "pending", "Reservation should still be Pending");
+  });
+
+  it("Should refund the guest in full and penalise a host who cancels", async () => {
+    const cancellingListing = await setupHost(cancellingHost);
+    const reservation = await book(cancellingListing);
+    const escrow = await pay(reservation);
+    await confirm(reservation, cancellingHost);
+
+    const guestBefore = await balance(tokenAccount(guest.publicKey));
+    await hostCancel(reservation, cancellingHost);
+
+    // TEST: The guest gets everything back, including the platform fee
+    assert.strictEqual(await balance(tokenAccount(guest.publicKey)) - guestBefore, TOTAL, "Guest should get the whole payment back");
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "refunded", "Escrow should be Refunded");
+
+    // TEST: The cancellation is recorded and 10% of the total is owed from future payouts
+    const hostAccount = await program.account.host.fetch(getHostAddress(cancellingHost.publicKey, program.programId)[0]);
+    assert.strictEqual(hostAccount.cancellations.toNumber(), 1, "Host should have one cancellation");
+    assert.strictEqual(hostAccount.pendingPenalty.toNumber(), TOTAL / 10, "Host should owe 10% of the total");
+  });
+
+  it("Should refuse to host-cancel a reservation that is not confirmed", async () => {
+    const reservation = await book();
+    await pay(reservation);
+
+    // Pending reservations are declined instead
+    await expectError(hostCancel(reservation), "ReservationNotConfirmed");
+  });
+
+  it("Should check a guest in on the first night and complete the stay at check-out", async () => {
+    const reservation = await book();
+    await startStay(reservation);
+
+    let reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "checkedIn", "Reservation should be CheckedIn");
+    assert.strictEqual(reservationAccount.checkedInAt.toNumber(), reservationAccount.startDate.toNumber());
+
+    const bookingsBefore = (await program.account.listing.fetch(listing)).totalBookings.toNumber();
+    await warpTo(reservationAccount.endDate.toNumber());
+    await complete(reservation);
+
+    reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "completed", "Reservation should be Completed");
+    assert.strictEqual((await program.account.listing.fetch(listing)).totalBookings.toNumber(), bookingsBefore + 1, "Listing should count the stay");
+  });
+
+  it("Should refuse to check in before the stay or complete it before check-out", async () => {
+    const reservation = await book();
+    await pay(reservation);
+    await confirm(reservation);
+
+    // TEST: The day before the first night
+    await expectError(checkIn(reservation), "CheckInNotOpen");
+
+    // TEST: Checked in, but the last night is not over
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await warpTo(reservationAccount.startDate.toNumber());
+    await checkIn(reservation);
+    await expectError(complete(reservation), "StayNotOver");
+  });
+
+  it("Should let anyone crank a due escrow for a share of the platform fee", async () => {
+    const crankerTokenAccount = await createTokenAccount(stranger.publicKey);
+    const reservation = await book();
+    const escrow = await startStay(reservation);
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await warpTo(reservationAccount.endDate.toNumber());
+
+    const hostBefore = await balance(tokenAccount(host.publicKey));
+    const treasuryBefore = await balance(platformTreasury);
+    const crankerBefore = await balance(crankerTokenAccount);
+    await crank(escrow);
+
+    // TEST: The bounty is 10% of the platform fee, the host's share is untouched
+    assert.strictEqual(await balance(crankerTokenAccount) - crankerBefore, FEE / 10, "Cranker should get the bounty");
+    assert.strictEqual(await balance(platformTreasury) - treasuryBefore, FEE - FEE / 10, "Treasury should get the rest of the fee");
+    assert.strictEqual(await balance(tokenAccount(host.publicKey)) - hostBefore, HOST_AMOUNT, "Host should get the stay");
+
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(Object.keys(escrowAccount.status)[0], "released", "Escrow should be Released");
+  });
+
+  it("Should refuse to crank an escrow before its release date", async () => {
+    await createTokenAccount(stranger.publicKey);
+    const reservation = await book();
+    const escrow = await startStay(reservation);
+
+    await expectError(crank(escrow), "ReleaseNotYetAllowed");
+  });
+
+  it("Should let anyone expire a reservation left unpaid past its deadline", async () => {
+    const reservation = await book();
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    const [guest_pkey] = getGuestAddress(guest.publicKey, program.programId);
+    const openBefore = (await program.account.guest.fetch(guest_pkey)).openReservationCount.toNumber();
+
+    await warpTo(reservationAccount.paymentDeadline.toNumber() + 1);
+    await expire(reservation);
+
+    // TEST: The reservation is closed to the guest and its nights can be booked again
+    assert.isNull(await program.account.reservation.fetchNullable(reservation), "Reservation should be closed");
+    assert.strictEqual((await program.account.guest.fetch(guest_pkey)).openReservationCount.toNumber(), openBefore - 1, "Guest open reservations should drop");
+
+    nextStartDay = reservationAccount.startDate.toNumber() / DAY;
+    const rebooked = await program.account.reservation.fetch(await book());
+    assert.isTrue(rebooked.startDate.eq(reservationAccount.startDate), "Expired nights should be bookable again");
+  });
+
+  it("Should refuse to expire a reservation before its payment deadline", async () => {
+    const reservation = await book();
+
+    await expectError(expire(reservation), "NotExpired");
+    assert.isNotNull(await program.account.reservation.fetchNullable(reservation), "Reservation should still exist");
+  });
+
+  it("Should refund under the policy agreed at booking, not the listing's current one", async () => {
+    const reservation = await book(listing, 3);
+    await pay(reservation);
+    await confirm(reservation);
+
+    // The host makes the listing non-refundable after the stay is confirmed
+    await updateListing({ cancellationPolicy: { nonRefundable: {} } });
+    try {
+      const guestBefore = await balance(tokenAccount(guest.publicKey));
+      await cancel(reservation);
+
+      // TEST: More than 24 hours before check-in, the flexible policy still refunds the stay
+      assert.strictEqual(await balance(tokenAccount(guest.publicKey)) - guestBefore, HOST_AMOUNT, "Guest should get the stay back");
+    } finally {
+      await updateListing({ cancellationPolicy: { flexible: { fullRefundHours: 24 } } });
+    }
+  });
+
+  it("Should keep the accepted mint while escrows still hold funds", async () => {
+    const platformConfig = getPlatformConfigAddress(program.programId);
+    const reservation = await book();
+    const escrow = await pay(reservation);
+
+    const otherMint = Keypair.generate();
+    await program.methods.initializeToken(0, new BN(0))
+    .accounts({
+      creator: platformAuthority.publicKey,
+      mint: otherMint.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
-      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([platformAuthority, otherMint])
+    .rpc();
+    const otherTreasury = await createTokenAccount(platformAuthority.publicKey, otherMint.publicKey);
+
+    // TEST: Switching would strand every funded vault on the old mint
+    await expectError(
+      program.methods.updatePlatformConfig(null, null, null, null, null, null, null)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        platformConfig: platformConfig,
+        mint: otherMint.publicKey,
+        platformTreasury: otherTreasury,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc(),
+      "EscrowsOutstanding"
+    );
+    const config = await program.account.platformConfig.fetch(platformConfig);
+    assert.isTrue(config.acceptedMint.equals(mint.publicKey), "Accepted mint should be unchanged");
+
+    // TEST: Settling an escrow takes it off the count
+    await refund(escrow);
+    const settled = await program.account.platformConfig.fetch(platformConfig);
+    assert.strictEqual(settled.openEscrowCount.toNumber(), config.openEscrowCount.toNumber() - 1, "Refunded escrow should no longer be open");
+  });
+
+  it("Should book and pay for a listing in one transaction", async () => {
+    const guestBefore = await balance(tokenAccount(guest.publicKey));
+    const { reservation, escrow } = await bookAndPay();
+
+    // TEST: The guest pays the price quoted on-chain, held in the escrow vault
+    assert.strictEqual(guestBefore - await balance(tokenAccount(guest.publicKey)), TOTAL, "Guest should pay the total price");
+    assert.strictEqual(await balance(getEscrowVaultAddress(escrow, program.programId)), TOTAL, "Vault should hold the total price");
+
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(Object.keys(reservationAccount.status)[0], "pending", "Reservation should await the host");
+    assert.strictEqual(Object.keys(reservationAccount.paymentStatus)[0], "paid", "Reservation should be paid");
+    assert.isTrue(reservationAccount.paymentEscrow.equals(escrow), "Reservation should link the escrow");
+    const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+    assert.strictEqual(escrowAccount.amount.toNumber(), TOTAL);
+    assert.strictEqual(escrowAccount.platformFee.toNumber(), FEE);
+  });
+
+  it("Should roll back the whole booking when the payment fails", async () => {
+    await setupGuest(brokeGuest);
+    await createTokenAccount(brokeGuest.publicKey);
+
+    const listingBefore = await program.account.listing.fetch(listing);
+    const [reservation] = getReservationAddress(brokeGuest.publicKey, 0, program.programId);
+    const bookingIndex = getBookingIndexAddress(listing, listingBefore.bookingCount.toNumber(), program.programId);
+    const startDay = Math.max(nextStartDay, Math.floor((await now()) / DAY) + 1);
+
+    // TEST: The guest's token account is empty, so the transfer to the vault fails
+    await expectError(bookAndPay(brokeGuest), "insufficient funds");
+
+    // TEST: Neither the reservation nor its booking index entry were created
+    assert.isNull(await program.account.reservation.fetchNullable(reservation), "Reservation should not exist");
+    assert.isNull(await program.account.bookingIndex.fetchNullable(bookingIndex), "Booking index entry should not exist");
+    const listingAfter = await program.account.listing.fetch(listing);
+    assert.isTrue(listingAfter.bookingCount.eq(listingBefore.bookingCount), "Booking count should be unchanged");
+    assert.isTrue(listingAfter.openReservationCount.eq(listingBefore.openReservationCount), "Open reservations should be unchanged");
+    const brokeAccount = await program.account.guest.fetch(getGuestAddress(brokeGuest.publicKey, program.programId)[0]);
+    assert.strictEqual(brokeAccount.reservationCount.toNumber(), 0, "Guest reservation count should be unchanged");
+
+    // TEST: The nights were never held, another guest can book them
+    nextStartDay = startDay;
+    const rebooked = await program.account.reservation.fetch(await book());
+    assert.strictEqual(rebooked.startDate.toNumber(), startDay * DAY, "Nights should still be free");
+  });
+
+  it("Should refuse to check in to an unpaid stay and leave it to expire", async () => {
+    const reservation = await book();
+    await confirm(reservation);
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await warpTo(reservationAccount.startDate.toNumber());
+
+    // TEST: Confirmed but never paid
+    await expectError(checkIn(reservation), "NotPaid");
+
+    // TEST: Still confirmed and unpaid, so it can be expired once the deadline passes
+    await warpTo(reservationAccount.startDate.toNumber() + 1);
+    await expire(reservation);
+    assert.isNull(await program.account.reservation.fetchNullable(reservation), "Reservation should be expired");
+  });
+
+  it("Should pay the host exactly its share when the mint caps its transfer fee", async () => {
+    // 5% transfer fee capped at 10 tokens, well below 5% of any stay
+    const MAX_FEE = 10;
+    const restoreMint = await useFeeMint(500, MAX_FEE);
+    try {
+      const guestBefore = await balance(tokenAccount(guest.publicKey));
+      const reservation = await book();
+      const escrow = await startStay(reservation);
+
+      // TEST: The guest pays the capped fee on top, the vault holds exactly the total
+      assert.strictEqual(guestBefore - await balance(tokenAccount(guest.publicKey)), TOTAL + MAX_FEE, "Guest should pay the capped fee on top");
+      assert.strictEqual(await balance(getEscrowVaultAddress(escrow, program.programId)), TOTAL, "Vault should hold the total price");
+
+      const reservationAccount = await program.account.reservation.fetch(reservation);
+      await warpTo(reservationAccount.endDate.toNumber());
+      const hostBefore = await balance(tokenAccount(host.publicKey));
+      const treasuryBefore = await balance(platformTreasury);
+      await release(escrow);
+
+      // TEST: The host nets its full share, both transfer fees come out of the platform fee
+      // The host transfer pays the capped fee, the 90 tokens swept to the treasury pay 5%
+      const sweptFee = Math.ceil((FEE - MAX_FEE) * 500 / 10000);
+      assert.strictEqual(await balance(tokenAccount(host.publicKey)) - hostBefore, HOST_AMOUNT, "Host should receive exactly its share");
+      assert.strictEqual(await balance(platformTreasury) - treasuryBefore, FEE - MAX_FEE - sweptFee, "Treasury should keep the fee less the transfer fees");
+      assert.strictEqual(await balance(getEscrowVaultAddress(escrow, program.programId)), 0, "Vault should be empty");
+    } finally {
+      await restoreMint();
+    }
+  });
+
+  it("Should refuse to release when the vault cannot cover the transfer fee", async () => {
+    // 10% transfer fee with no practical cap, more than the 5% platform fee can absorb
+    const restoreMint = await useFeeMint(1000, 1_000_000);
+    try {
+      const reservation = await book();
+      const escrow = await startStay(reservation);
+      const reservationAccount = await program.account.reservation.fetch(reservation);
+      await warpTo(reservationAccount.endDate.toNumber());
+      const hostBefore = await balance(tokenAccount(host.publicKey));
+
+      // TEST: The host is not underpaid, the release fails and the vault is untouched
+      await expectError(release(escrow), "InsufficientVaultBalance");
+      assert.strictEqual(await balance(tokenAccount(host.publicKey)), hostBefore, "Host should receive nothing");
+      assert.strictEqual(await balance(getEscrowVaultAddress(escrow, program.programId)), TOTAL, "Vault should still hold the total price");
+      const escrowAccount = await program.account.paymentEscrow.fetch(escrow);
+      assert.strictEqual(Object.keys(escrowAccount.status)[0], "funded", "Escrow should still be Funded");
+
+      // The stuck escrow can still be settled by a refund
+      await refund(escrow);
+    } finally {
+      await restoreMint();
+    }
+  });
+
+  it("Should close a resolved dispute with its escrow, rent back to whoever opened it", async () => {
+    const reservation = await book();
+    const escrow = await startStay(reservation);
+    const dispute = getDisputeAddress(escrow, program.programId);
+    await openDispute(escrow, host);
+    await resolveDispute(escrow, 5000);
+
+    // TEST: The guest cannot close the escrow and strand the host's dispute
+    await expectError(closePaymentEscrow(escrow, false), "MissingDispute");
+
+    const disputeRent = Number((await context.banksClient.getAccount(dispute)).lamports);
+    const hostLamportsBefore = Number((await context.banksClient.getAccount(host.publicKey)).lamports);
+    await closePaymentEscrow(escrow);
+
+    // TEST: Both accounts are gone, the host who opened the dispute gets its rent back
+    assert.isNull(await program.account.paymentEscrow.fetchNullable(escrow), "Escrow should be closed");
+    assert.isNull(await program.account.dispute.fetchNullable(dispute), "Dispute should be closed");
+    assert.strictEqual(
+      Number((await context.banksClient.getAccount(host.publicKey)).lamports) - hostLamportsBefore,
+      disputeRent,
+      "Host should get the dispute rent back"
+    );
+  });
+
+  it("Should let a host with past cancellations close once its penalty is paid", async () => {
+    const [host_pkey] = getHostAddress(cancellingHost.publicKey, program.programId);
+    const [cancellingListing] = getListingAddress(cancellingHost.publicKey, 0, program.programId);
+    await createTokenAccount(cancellingHost.publicKey);
+
+    // The penalty from the earlier host cancellation comes out of the next payout
+    const reservation = await book(cancellingListing);
+    const escrow = await pay(reservation);
+    await confirm(reservation, cancellingHost);
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    await warpTo(reservationAccount.startDate.toNumber());
+    await checkIn(reservation);
+    await warpTo(reservationAccount.endDate.toNumber());
+    await release(escrow);
+    await complete(reservation, cancellingHost);
+    const hostAccount = await program.account.host.fetch(host_pkey);
+    assert.strictEqual(hostAccount.pendingPenalty.toNumber(), 0, "Penalty should be paid off");
+    assert.strictEqual(hostAccount.cancellations.toNumber(), 1, "Cancellation should still be on record");
+
+    // Close both reservations of the listing, including the one the host cancelled
+    const bookingIndex = getBookingIndexAddress(cancellingListing, 0, program.programId);
+    await closeReservation((await program.account.bookingIndex.fetch(bookingIndex)).reservation);
+    await closeReservation(reservation);
+    await closeListing(cancellingHost, cancellingListing);
+
+    // TEST: The cancellation record does not keep the host open
+    await closeHost(cancellingHost);
+    assert.isNull(await program.account.host.fetchNullable(host_pkey), "Host should be closed");
+  });
+
+  it("Should migrate a legacy host and its listing, dropping the host's personal data", async () => {
+    const LEGACY_HOST_SPACE = 1161;    // LegacyHost::INIT_SPACE
+    const LEGACY_LISTING_SPACE = 1008; // LegacyListing::INIT_SPACE
+    const CREATED_AT = 1_600_000_000;
+    const email = "legacy-host@example.com";
+    const hashedPassword = "$2b$10$legacyhostpasswordhash";
+    const userProfile = getUserProfileAddress(legacyUser.publicKey, program.programId);
+    const [host_pkey, hostBump] = getHostAddress(legacyUser.publicKey, program.programId);
+    const [listing_pkey] = getListingAddress(legacyUser.publicKey, 0, program.programId);
+
+    await setLegacyAccount(host_pkey, "Host", LEGACY_HOST_SPACE, [
+      legacyUser.publicKey.toBuffer(),
+      borshString("Legacy Host"),
+      borshString(email),
+      borshString("https://example.com/legacy-host.jpg"),
+      borshString(hashedPassword),
+      borshU64(CREATED_AT),
+      borshU64(1), // listing_count
+      Buffer.from([hostBump]),
+    ]);
+    const legacyHostLamports = await lamports(host_pkey);
+    const userBefore = await lamports(legacyUser.publicKey);
+
+    const commitment = Array.from(Buffer.alloc(32, 7));
+    await program.methods.migrateHost(commitment, "ipfs://profiles/legacy-host")
+    .accounts({
+      hostAuthority: legacyUser.publicKey,
+      userProfile: userProfile,
+      host: host_pkey,
       systemProgram: anchor.web3.SystemProgram.programId
     })
-    .signers([guest])
-    .rpc({ commitment: "confirmed" });
+    .signers([legacyUser])
+    .rpc();
+
+    // TEST: The public fields move to a new profile, the host keeps its counters
+    const profile = await program.account.userProfile.fetch(userProfile);
+    assert.isTrue(profile.authority.equals(legacyUser.publicKey));
+    assert.strictEqual(profile.name, "Legacy Host");
+    assert.strictEqual(profile.imageUrl, "https://example.com/legacy-host.jpg");
+    assert.deepEqual(profile.profileCommitment, commitment);
+    assert.strictEqual(profile.metadataUri, "ipfs://profiles/legacy-host");
+    assert.strictEqual(profile.createdAt.toNumber(), CREATED_AT);
+    assert.strictEqual(profile.roles, 1, "Profile should have the host role");
 
-    // Test Case 2: Release date in the future
-    const escrowId2 = 31;
-    const [escrow2_pkey] = getPaymentEscrowAddress(
-      reservation_pkey,
-      escrowId2,
-      program.programId
+    const hostAccount = await program.account.host.fetch(host_pkey);
+    assert.isTrue(hostAccount.hostAuthor.equals(legacyUser.publicKey));
+    assert.isTrue(hostAccount.profile.equals(userProfile), "Host should point at the new profile");
+    assert.strictEqual(hostAccount.createdAt.toNumber(), CREATED_AT);
+    assert.strictEqual(hostAccount.listingCount.toNumber(), 1);
+    assert.strictEqual(hostAccount.activeListingCount.toNumber(), 0, "Legacy listings count once migrated");
+    assert.strictEqual(hostAccount.bump, hostBump);
+
+    // TEST: The host shrinks, its spare rent pays for the new profile and the rest goes back to the signer
+    // The provider wallet pays the transaction fee, so the signer's balance only moves by rent
+    const hostData = await assertRewritten(host_pkey, "Host", hostAccount);
+    assert.isBelow(hostData.length, 8 + LEGACY_HOST_SPACE, "Host should shrink");
+    const hostLamports = await lamports(host_pkey);
+    assert.strictEqual(
+      await lamports(legacyUser.publicKey) - userBefore,
+      legacyHostLamports - hostLamports - await lamports(userProfile),
+      "Signer should get the host's spare rent back"
     );
 
-    const futureReleaseDate = new BN(Math.floor((Date.now() + 7 * 24 * 60 * 60 * 1000) / 1000)); // 7 days from now
+    // TEST: Email and password hash are gone from the account
+    assert.strictEqual(hostData.indexOf(email), -1, "Email should be wiped");
+    assert.strictEqual(hostData.indexOf(hashedPassword), -1, "Password hash should be wiped");
+
+    await setLegacyAccount(listing_pkey, "Listing", LEGACY_LISTING_SPACE, [
+      host_pkey.toBuffer(),
+      borshString("Legacy Loft"),
+      borshString("Listed before user profiles existed"),
+      borshString("https://example.com/legacy-loft.jpg"),
+      borshU64(CREATED_AT),
+      borshString("Lofts"),
+      Buffer.from([1, 1, 2]), // room, bathroom and guest counts
+      borshString("PT"),
+      borshU64(3),            // total_bookings
+      Buffer.from([1]),       // is_active
+      borshU64(PRICE),
+    ]);
+    const legacyListingLamports = await lamports(listing_pkey);
+
+    await program.methods.migrateListing({ moderate: { fullRefundHours: 48 } })
+    .accounts({
+      listingAuthority: legacyUser.publicKey,
+      userProfile: userProfile,
+      host: host_pkey,
+      listing: listing_pkey,
+      availability: getAvailabilityAddress(listing_pkey, program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([legacyUser])
+    .rpc();
 
-    await program.methods.initializePaymentEscrow(
-      new BN(escrowId2),
-      new BN(200),
-      futureReleaseDate
+    // TEST: The listing keeps its fields, gains the chosen policy and a calendar starting today
+    const listingAccount = await program.account.listing.fetch(listing_pkey);
+    assert.isTrue(listingAccount.host.equals(host_pkey));
+    assert.strictEqual(listingAccount.title, "Legacy Loft");
+    assert.strictEqual(listingAccount.description, "Listed before user profiles existed");
+    assert.strictEqual(listingAccount.category, "Lofts");
+    assert.strictEqual(listingAccount.guestCount, 2);
+    assert.strictEqual(listingAccount.locationValue, "PT");
+    assert.strictEqual(listingAccount.totalBookings.toNumber(), 3);
+    assert.isTrue(listingAccount.isActive);
+    assert.strictEqual(listingAccount.price.toNumber(), PRICE);
+    assert.strictEqual(listingAccount.createdAt.toNumber(), CREATED_AT);
+    assert.deepEqual(listingAccount.cancellationPolicy, { moderate: { fullRefundHours: 48 } });
+    assert.strictEqual(listingAccount.bookingCount.toNumber(), 0);
+    const availability = await program.account.listingAvailability.fetch(getAvailabilityAddress(listing_pkey, program.programId));
+    assert.strictEqual(availability.windowStartDay.toNumber(), Math.floor((await now()) / DAY));
+    assert.strictEqual((await program.account.host.fetch(host_pkey)).activeListingCount.toNumber(), 1, "Migrated listing should count as active");
+
+    // TEST: The listing grows, the signer tops up its rent
+    await assertRewritten(listing_pkey, "Listing", listingAccount);
+    assert.isAbove(await lamports(listing_pkey), legacyListingLamports, "Listing rent should be topped up");
+
+    // TEST: Migrated accounts are not migrated twice
+    await expectError(
+      program.methods.migrateHost(commitment, "ipfs://profiles/legacy-host")
+      .accounts({
+        hostAuthority: legacyUser.publicKey,
+        userProfile: userProfile,
+        host: host_pkey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      })
+      .signers([legacyUser])
+      .rpc(),
+      "AlreadyMigrated"
+    );
+  });
+
+  it("Should migrate a legacy guest into the existing profile, dropping its personal data", async () => {
+    const LEGACY_GUEST_SPACE = 1233; // LegacyGuest::INIT_SPACE
+    const CREATED_AT = 1_650_000_000;
+    const RESERVATION_COUNT = 5;     // One above the guest's highest legacy reservation id
+    const email = "legacy-guest@example.com";
+    const hashedPassword = "$2b$10$legacyguestpasswordhash";
+    const phoneNumber = "+351912345678";
+    const userProfile = getUserProfileAddress(legacyUser.publicKey, program.programId);
+    const [guest_pkey, guestBump] = getGuestAddress(legacyUser.publicKey, program.programId);
+
+    await setLegacyAccount(guest_pkey, "Guest", LEGACY_GUEST_SPACE, [
+      legacyUser.publicKey.toBuffer(),
+      borshString("Legacy Guest"),
+      borshString(email),
+      borshString("https://example.com/legacy-guest.jpg"),
+      borshString(hashedPassword),
+      borshU64(CREATED_AT),
+      borshString(phoneNumber),
+      borshU64(631152000), // date_of_birth
+      borshString("pt-PT"),
+      Buffer.from([guestBump]),
+    ]);
+    const legacyGuestLamports = await lamports(guest_pkey);
+    const userBefore = await lamports(legacyUser.publicKey);
+    const profileBefore = await program.account.userProfile.fetch(userProfile);
+
+    await program.methods.migrateGuest(
+      Array.from(Buffer.alloc(32, 9)),
+      "ipfs://profiles/legacy-guest",
+      new BN(RESERVATION_COUNT),
     )
     .accounts({
-      guestAuthority: guest.publicKey,
-      reservation: reservation_pkey,
-      paymentEscrow: escrow2_pkey,
-      mint: mint.publicKey,
-      guestTokenAccount: guestTokenAccount,
-      platformTreasury: platformTreasuryATA,
-      tokenProgram: TOKEN_2022_PROGRAM_ID,
-      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      guestAuthority: legacyUser.publicKey,
+      userProfile: userProfile,
+      guest: guest_pkey,
+      nextReservation: getReservationAddress(legacyUser.publicKey, RESERVATION_COUNT, program.programId)[0],
       systemProgram: anchor.web3.SystemProgram.programId
     })
-    .signers([guest])
-    .rpc({ commitment: "confirmed" });
+    .signers([legacyUser])
+    .rpc();
 
-    // Verify the escrows
-    const escrow1Account = await program.account.paymentEscrow.fetch(escrow1_pkey);
-    const escrow2Account = await program.account.paymentEscrow.fetch(escrow2_pkey);
+    // TEST: The host migrated first, so the profile keeps its fields and gains the guest role
+    const profile = await program.account.userProfile.fetch(userProfile);
+    assert.strictEqual(profile.name, profileBefore.name, "Profile name should be kept");
+    assert.strictEqual(profile.metadataUri, profileBefore.metadataUri, "Metadata URI should be kept");
+    assert.deepEqual(profile.profileCommitment, profileBefore.profileCommitment, "Commitment should be kept");
+    assert.strictEqual(profile.roles, 3, "Profile should have both roles");
 
-    console.log("Past release escrow:", {
-      releaseDate: new Date(escrow1Account.releaseDate.toNumber() * 1000).toISOString(),
-      isPastDue: escrow1Account.releaseDate.toNumber() < Math.floor(Date.now() / 1000)
-    });
+    const guestAccount = await program.account.guest.fetch(guest_pkey);
+    assert.isTrue(guestAccount.guestAuthor.equals(legacyUser.publicKey));
+    assert.isTrue(guestAccount.profile.equals(userProfile), "Guest should point at the profile");
+    assert.strictEqual(guestAccount.createdAt.toNumber(), CREATED_AT);
+    assert.strictEqual(guestAccount.reservationCount.toNumber(), RESERVATION_COUNT, "Counter should resume above legacy ids");
+    assert.strictEqual(guestAccount.openReservationCount.toNumber(), 0);
+    assert.strictEqual(guestAccount.bump, guestBump);
+
+    // TEST: The guest shrinks and all of its spare rent goes back to the signer
+    const guestData = await assertRewritten(guest_pkey, "Guest", guestAccount);
+    assert.isBelow(guestData.length, 8 + LEGACY_GUEST_SPACE, "Guest should shrink");
+    assert.strictEqual(
+      await lamports(legacyUser.publicKey) - userBefore,
+      legacyGuestLamports - await lamports(guest_pkey),
+      "Signer should get the guest's spare rent back"
+    );
 
-    console.log("Future release escrow:", {
-      releaseDate: new Date(escrow2Account.releaseDate.toNumber() * 1000).toISOString(),
-      daysUntilRelease: Math.round((escrow2Account.releaseDate.toNumber() * 1000 - Date.now()) / (24 * 60 * 60 * 1000))
+    // TEST: Email, password hash and phone number are gone from the account
+    assert.strictEqual(guestData.indexOf(email), -1, "Email should be wiped");
+    assert.strictEqual(guestData.indexOf(hashedPassword), -1, "Password hash should be wiped");
+    assert.strictEqual(guestData.indexOf(phoneNumber), -1, "Phone number should be wiped");
+  });
+
+  it("Should update only the listing fields that are given", async () => {
+    const editingListing = await setupHost(editingHost);
+    const before = await program.account.listing.fetch(editingListing);
+    await warpTo((await now()) + 60);
+
+    await updateListing({ title: "Renovated Cabin", price: new BN(1500) }, editingHost, editingListing);
+
+    // TEST: The given fields change, every other field is left as it was
+    let listingAccount = await program.account.listing.fetch(editingListing);
+    assert.strictEqual(listingAccount.title, "Renovated Cabin");
+    assert.strictEqual(listingAccount.price.toNumber(), 1500);
+    assert.strictEqual(listingAccount.description, before.description);
+    assert.strictEqual(listingAccount.imageUrl, before.imageUrl);
+    assert.strictEqual(listingAccount.category, before.category);
+    assert.strictEqual(listingAccount.roomCount, before.roomCount);
+    assert.strictEqual(listingAccount.bathroomCount, before.bathroomCount);
+    assert.strictEqual(listingAccount.guestCount, before.guestCount);
+    assert.strictEqual(listingAccount.locationValue, before.locationValue);
+    assert.deepEqual(listingAccount.cancellationPolicy, before.cancellationPolicy);
+    assert.isAbove(listingAccount.updatedAt.toNumber(), before.updatedAt.toNumber(), "updated_at should be bumped");
+
+    await updateListing({ guestCount: 6, cancellationPolicy: { strict: { partialRefundHours: 72 } } }, editingHost, editingListing);
+
+    // TEST: A later update keeps the earlier one
+    listingAccount = await program.account.listing.fetch(editingListing);
+    assert.strictEqual(listingAccount.guestCount, 6);
+    assert.deepEqual(listingAccount.cancellationPolicy, { strict: { partialRefundHours: 72 } });
+    assert.strictEqual(listingAccount.title, "Renovated Cabin");
+    assert.strictEqual(listingAccount.price.toNumber(), 1500);
+  });
+
+  it("Should reject invalid listing updates without writing anything", async () => {
+    const [editingListing] = getListingAddress(editingHost.publicKey, 0, program.programId);
+    const before = await program.account.listing.fetch(editingListing);
+    const update = (fields: Parameters<typeof updateListing>[0]) => updateListing(fields, editingHost, editingListing);
+
+    await expectError(update({ title: " " }), "EmptyTitle");
+    await expectError(update({ title: "x".repeat(65) }), "TitleTooLong");
+    await expectError(update({ description: "x".repeat(301) }), "DescriptionTooLong");
+    await expectError(update({ imageUrl: "ftp://example.com/cabin.jpg" }), "InvalidImageUrl");
+    await expectError(update({ roomCount: 0 }), "InvalidRoomCount");
+    await expectError(update({ guestCount: 0 }), "InvalidGuestCount");
+    await expectError(update({ price: new BN(0) }), "InvalidPrice");
+
+    // TEST: A valid field next to an invalid one is not written either
+    await expectError(update({ title: "Half Updated Cabin", price: new BN(0) }), "InvalidPrice");
+    const listingAccount = await program.account.listing.fetch(editingListing);
+    assert.strictEqual(listingAccount.title, before.title, "Title should be unchanged");
+    assert.strictEqual(listingAccount.price.toNumber(), before.price.toNumber(), "Price should be unchanged");
+    assert.strictEqual(listingAccount.updatedAt.toNumber(), before.updatedAt.toNumber(), "updated_at should be unchanged");
+  });
+
+  it("Should only let the listing's host update or deactivate it", async () => {
+    const [editingListing] = getListingAddress(editingHost.publicKey, 0, program.programId);
+
+    // TEST: Another host signs with its own host account
+    await expectError(updateListing({ title: "Taken Over Cabin" }, host, editingListing), "UnauthorizedHost");
+    await expectError(setListingActive(false, host, editingListing), "UnauthorizedHost");
+
+    const listingAccount = await program.account.listing.fetch(editingListing);
+    assert.strictEqual(listingAccount.title, "Renovated Cabin", "Title should be unchanged");
+    assert.isTrue(listingAccount.isActive, "Listing should still be active");
+  });
+
+  it("Should stop bookings while a listing is inactive", async () => {
+    const [editingListing] = getListingAddress(editingHost.publicKey, 0, program.programId);
+
+    await setListingActive(false, editingHost, editingListing);
+    assert.isFalse((await program.account.listing.fetch(editingListing)).isActive, "Listing should be inactive");
+    await expectError(book(editingListing), "ListingInactive");
+
+    // TEST: Reactivated, the listing takes bookings again
+    await setListingActive(true, editingHost, editingListing);
+    assert.isTrue((await program.account.listing.fetch(editingListing)).isActive, "Listing should be active");
+    const reservation = await book(editingListing);
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    assert.strictEqual(reservationAccount.pricePerNight.toNumber(), 1500, "Booking should use the updated price");
+  });
+
+  it("Should update only the profile fields that are given", async () => {
+    const userProfile = getUserProfileAddress(editingHost.publicKey, program.programId);
+    const before = await program.account.userProfile.fetch(userProfile);
+    await warpTo((await now()) + 60);
+
+    await updateUserProfile({ name: "Renamed User", imageUrl: "https://example.com/avatar.png" });
+
+    // TEST: The given fields change, the rest are left as they were and updated_at is bumped
+    let profile = await program.account.userProfile.fetch(userProfile);
+    assert.strictEqual(profile.name, "Renamed User");
+    assert.strictEqual(profile.imageUrl, "https://example.com/avatar.png");
+    assert.deepEqual(profile.profileCommitment, before.profileCommitment);
+    assert.strictEqual(profile.metadataUri, before.metadataUri);
+    assert.strictEqual(profile.preferredLanguage, before.preferredLanguage);
+    assert.strictEqual(profile.roles, before.roles);
+    assert.strictEqual(profile.createdAt.toNumber(), before.createdAt.toNumber());
+    assert.isAbove(profile.updatedAt.toNumber(), before.updatedAt.toNumber(), "updated_at should be bumped");
+
+    const updatedAt = profile.updatedAt.toNumber();
+    await warpTo((await now()) + 60);
+    const commitment = Array.from(Buffer.alloc(32, 3));
+    await updateUserProfile({
+      imageUrl: "",
+      profileCommitment: commitment,
+      metadataUri: "ar://profiles/renamed-user",
+      preferredLanguage: "pt-BR",
     });
 
-    // ✅ TEST: Verify time logic
-    const currentTimeSeconds = Math.floor(Date.now() / 1000);
-    assert.isTrue(escrow1Account.releaseDate.toNumber() < currentTimeSeconds, "Past release date should be in the past");
-    assert.isTrue(escrow2Account.releaseDate.toNumber() > currentTimeSeconds, "Future release date should be in the future");
-    
-    // ✅ TEST: Verify release dates match what we set
-    assert.strictEqual(escrow1Account.releaseDate.toString(), pastReleaseDate.toString(), "Past release date should match");
-    assert.strictEqual(escrow2Account.releaseDate.toString(), futureReleaseDate.toString(), "Future release date should match");
+    // TEST: A later update keeps the earlier one, an empty image URL removes the picture
+    profile = await program.account.userProfile.fetch(userProfile);
+    assert.strictEqual(profile.name, "Renamed User");
+    assert.strictEqual(profile.imageUrl, "");
+    assert.deepEqual(profile.profileCommitment, commitment);
+    assert.strictEqual(profile.metadataUri, "ar://profiles/renamed-user");
+    assert.strictEqual(profile.preferredLanguage, "pt-BR");
+    assert.isAbove(profile.updatedAt.toNumber(), updatedAt, "updated_at should be bumped again");
+  });
 
-    // Guest balance: 7450 - 300 - 200 = ~6950 tokens remaining
-    console.log("✅ Time-based release date tests completed - guest balance: ~6950 tokens remaining");
+  it("Should reject invalid profile updates without writing anything", async () => {
+    const userProfile = getUserProfileAddress(editingHost.publicKey, program.programId);
+    const before = await program.account.userProfile.fetch(userProfile);
+
+    await expectError(updateUserProfile({ name: " " }), "EmptyName");
+    await expectError(updateUserProfile({ name: "x".repeat(33) }), "NameTooLong");
+    await expectError(updateUserProfile({ imageUrl: "https://example.com/" + "x".repeat(500) }), "ImageUrlTooLong");
+    await expectError(updateUserProfile({ imageUrl: "avatar.png" }), "InvalidImageUrl");
+    await expectError(updateUserProfile({ metadataUri: "ipfs://" + "x".repeat(200) }), "MetadataUriTooLong");
+    await expectError(updateUserProfile({ metadataUri: "ftp://example.com/profile.json" }), "InvalidMetadataUri");
+    await expectError(updateUserProfile({ preferredLanguage: "x".repeat(33) }), "PreferredLanguageTooLong");
+    await expectError(updateUserProfile({ preferredLanguage: "en_US" }), "InvalidPreferredLanguage");
+
+    // TEST: A valid field next to an invalid one is not written either
+    await expectError(updateUserProfile({ name: "Half Renamed", preferredLanguage: "en US" }), "InvalidPreferredLanguage");
+    const profile = await program.account.userProfile.fetch(userProfile);
+    assert.strictEqual(profile.name, before.name, "Name should be unchanged");
+    assert.strictEqual(profile.preferredLanguage, before.preferredLanguage, "Preferred language should be unchanged");
+    assert.strictEqual(profile.updatedAt.toNumber(), before.updatedAt.toNumber(), "updated_at should be unchanged");
   });
+});
 
-  it("Should test PDA generation consistency", async () => {
-    // Test that PDA generation is deterministic and consistent
-    const testReservation = anchor.web3.Keypair.generate().publicKey;
-    const testEscrowId = 999;
+// bankrun does not report inner instructions, so the self-CPIs of emit_cpi! are read back through LiteSVM
+describe("emitted events", () => {
+  const DAY = 24 * 60 * 60;
+  const PRICE = 1000;               // per night
+  const NIGHTS = 2;
+  const TOTAL = PRICE * NIGHTS;
+  const FEE = (TOTAL * 500) / 10000; // 5% platform fee
+  // anchor_lang::event::EVENT_IX_TAG_LE, first bytes of every emit_cpi! self-invocation
+  const EVENT_IX_TAG = Buffer.from([0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d]);
+
+  const payer = Keypair.generate();
+  const host = Keypair.generate();
+  const guest = Keypair.generate();
+  const platformAuthority = Keypair.generate(); // Mint creator and release authority
+  const mint = Keypair.generate();
 
-    // Generate PDA multiple times
-    const [pda1] = getPaymentEscrowAddress(testReservation, testEscrowId, program.programId);
-    const [pda2] = getPaymentEscrowAddress(testReservation, testEscrowId, program.programId);
-    const [pda3] = getPaymentEscrowAddress(testReservation, testEscrowId, program.programId);
+  let svm: LiteSVM;
+  let program: Program<AirbnbBlockhain>;
+  let listing: PublicKey;
+  let platformTreasury: PublicKey;
+  let nextStartDay = 0;
+  let nextEscrowId = 1;
+
+  before(async () => {
+    svm = new LiteSVM();
+    svm.addProgramFromFile(new PublicKey(IDL.address), path.join(__dirname, "../target/deploy/airbnb_blockhain.so"));
+    for (const keypair of [payer, host, guest, platformAuthority]) {
+      svm.airdrop(keypair.publicKey, BigInt(10 * anchor.web3.LAMPORTS_PER_SOL));
+    }
+    program = new Program<AirbnbBlockhain>(IDL as AirbnbBlockhain, new LiteSVMProvider(svm, new anchor.Wallet(payer)));
 
-    // ✅ TEST: PDAs should be identical
-    assert.strictEqual(pda1.toString(), pda2.toString(), "PDA generation should be deterministic");
-    assert.strictEqual(pda2.toString(), pda3.toString(), "PDA generation should be consistent");
+    // Start at midnight so stays line up with whole days
+    warpTo(Math.ceil(now() / DAY) * DAY);
 
-    // ✅ TEST: Different escrow IDs should generate different PDAs
-    const [differentPda] = getPaymentEscrowAddress(testReservation, testEscrowId + 1, program.programId);
-    assert.notStrictEqual(pda1.toString(), differentPda.toString(), "Different escrow IDs should generate different PDAs");
+    await program.methods.initializeToken(0, new BN(0))
+    .accounts({
+      creator: platformAuthority.publicKey,
+      mint: mint.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([platformAuthority, mint])
+    .rpc();
+
+    platformTreasury = tokenAccount(platformAuthority.publicKey);
+    await program.provider.sendAndConfirm(new Transaction().add(
+      ...[platformAuthority.publicKey, host.publicKey].map(owner =>
+        createAssociatedTokenAccountIdempotentInstruction(
+          payer.publicKey,
+          tokenAccount(owner),
+          owner,
+          mint.publicKey,
+          TOKEN_2022_PROGRAM_ID,
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        )
+      )
+    ));
+    await program.methods.mintToken(new BN(1_000_000))
+    .accounts({
+      creator: platformAuthority.publicKey,
+      mint: mint.publicKey,
+      recipient: guest.publicKey,
+      recipientAta: tokenAccount(guest.publicKey),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([platformAuthority])
+    .rpc();
 
-    // ✅ TEST: Different reservations should generate different PDAs
-    const [differentReservationPda] = getPaymentEscrowAddress(
-      anchor.web3.Keypair.generate().publicKey,
-      testEscrowId,
+    // Same config as the lifecycle suite, written directly for the same reason
+    const [platformConfig, bump] = PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode(PLATFORM_CONFIG_SEED)],
       program.programId
     );
-    assert.notStrictEqual(pda1.toString(), differentReservationPda.toString(), "Different reservations should generate different PDAs");
+    const data = await program.coder.accounts.encode("PlatformConfig", {
+      admin: payer.publicKey,
+      feeBps: 500,
+      acceptedMint: mint.publicKey,
+      mintDecimals: 9,
+      treasury: platformTreasury,
+      releaseAuthority: platformAuthority.publicKey,
+      arbiter: platformAuthority.publicKey,
+      refundFeePolicy: { retainPlatformFee: {} },
+      hostCancelPenaltyBps: 1000,
+      crankBountyBps: 1000,
+      openEscrowCount: new BN(0),
+      updatedAt: new BN(0),
+      bump,
+    });
+    svm.setAccount(platformConfig, {
+      lamports: anchor.web3.LAMPORTS_PER_SOL,
+      data,
+      owner: program.programId,
+      executable: false,
+    });
 
-    console.log("✅ PDA generation consistency tests passed!");
-    console.log("Sample PDA:", pda1.toString());
+    for (const user of [host, guest]) {
+      await program.methods.initializeUserProfile(
+        "Event User",
+        "",
+        Array.from(Keypair.generate().publicKey.toBytes()),
+        `ipfs://profiles/${user.publicKey.toBase58()}`,
+        "en",
+      )
+      .accounts({
+        authority: user.publicKey,
+        userProfile: getUserProfileAddress(user.publicKey, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId
+      })
+      .signers([user])
+      .rpc();
+    }
+
+    const [host_pkey] = getHostAddress(host.publicKey, program.programId);
+    [listing] = getListingAddress(host.publicKey, 0, program.programId);
+    await program.methods.initializeHost()
+    .accounts({
+      hostAuthority: host.publicKey,
+      userProfile: getUserProfileAddress(host.publicKey, program.programId),
+      host: host_pkey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([host])
+    .rpc();
+    await program.methods.initializeListing(
+      "Event Cabin",
+      "Cabin booked by the event tests",
+      "https://example.com/cabin.jpg",
+      "Cabins",
+      2, // room_count
+      1, // bathroom_count
+      4, // guest_count
+      "FR",
+      true,
+      new BN(PRICE),
+      { flexible: { fullRefundHours: 24 } },
+    )
+    .accounts({
+      listingAuthority: host.publicKey,
+      userProfile: getUserProfileAddress(host.publicKey, program.programId),
+      host: host_pkey,
+      listing: listing,
+      availability: getAvailabilityAddress(listing, program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([host])
+    .rpc();
+
+    await program.methods.initializeGuest()
+    .accounts({
+      guestAuthority: guest.publicKey,
+      userProfile: getUserProfileAddress(guest.publicKey, program.programId),
+      guest: getGuestAddress(guest.publicKey, program.programId)[0],
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([guest])
+    .rpc();
   });
 
-  it("Should complete full payment cycle", async () => {
-    console.log("🎯 Testing complete payment cycle: Guest pays → Release → Host receives");
-    
-    // REUSE existing guest, reservation, and token accounts
-    const reservationId = 1; // Existing reservation
-    const [reservation_pkey] = getReservationAddress(
-      guest.publicKey,
-      reservationId,
-      program.programId
-    );
+  function now() {
+    return Number(svm.getClock().unixTimestamp);
+  }
 
-    const guestTokenAccount = getAssociatedTokenAddressSync(
+  function warpTo(unixTimestamp: number) {
+    const clock = svm.getClock();
+    clock.unixTimestamp = BigInt(unixTimestamp);
+    svm.setClock(clock);
+    // A new blockhash, so a retried instruction is a new transaction
+    svm.expireBlockhash();
+  }
+
+  function tokenAccount(owner: PublicKey) {
+    return getAssociatedTokenAddressSync(
       mint.publicKey,
-      guest.publicKey,
+      owner,
       false,
       TOKEN_2022_PROGRAM_ID,
       ASSOCIATED_TOKEN_PROGRAM_ID
     );
+  }
 
-    // STEP 1: Create escrow with immediate release (past date)
-    const escrowId = 100;
-    const [escrow_pkey] = getPaymentEscrowAddress(
-      reservation_pkey,
-      escrowId,
-      program.programId
-    );
+  // Every event the transaction emitted through emit_cpi!, in order
+  // Each is a self-invocation whose data is the event tag, then the event discriminator and fields
+  function emittedEvents(signature: string) {
+    const meta = svm.getTransaction(anchor.utils.bytes.bs58.decode(signature));
+    if (!(meta instanceof TransactionMetadata)) {
+      return assert.fail(`Transaction ${signature} did not succeed`);
+    }
+    const events = [];
+    for (const innerInstructions of meta.innerInstructions()) {
+      for (const innerInstruction of innerInstructions) {
+        const data = Buffer.from(innerInstruction.instruction().data());
+        if (data.subarray(0, 8).equals(EVENT_IX_TAG)) {
+          events.push(program.coder.events.decode(anchor.utils.bytes.base64.encode(data.subarray(8))));
+        }
+      }
+    }
+    return events;
+  }
 
-    const paymentAmount = new BN(500);
-    const immediateReleaseDate = new BN(Math.floor((Date.now() - 1000) / 1000)); // Already passed
+  // Reserve and pay for the next free nights, returns the accounts and the signature
+  async function book() {
+    const startDay = Math.max(nextStartDay, Math.floor(now() / DAY) + 2);
+    nextStartDay = startDay + NIGHTS;
+    const escrowId = nextEscrowId++;
 
-    await program.methods.initializePaymentEscrow(
+    const [guest_pkey] = getGuestAddress(guest.publicKey, program.programId);
+    const guestAccount = await program.account.guest.fetch(guest_pkey);
+    const listingAccount = await program.account.listing.fetch(listing);
+    const [reservation] = getReservationAddress(guest.publicKey, guestAccount.reservationCount.toNumber(), program.programId);
+    const [escrow] = getPaymentEscrowAddress(reservation, escrowId, program.programId);
+
+    const signature = await program.methods.bookListing(
       new BN(escrowId),
-      paymentAmount,
-      immediateReleaseDate
+      new BN(startDay * DAY),
+      new BN((startDay + NIGHTS) * DAY),
+      2,
+      new BN((startDay + NIGHTS) * DAY),
     )
     .accounts({
       guestAuthority: guest.publicKey,
-      reservation: reservation_pkey,
-      paymentEscrow: escrow_pkey,
+      userProfile: getUserProfileAddress(guest.publicKey, program.programId),
+      guest: guest_pkey,
+      listing: listing,
+      host: listingAccount.host,
+      availability: getAvailabilityAddress(listing, program.programId),
+      reservation: reservation,
+      bookingIndex: getBookingIndexAddress(listing, listingAccount.bookingCount.toNumber(), program.programId),
+      paymentEscrow: escrow,
+      platformConfig: getPlatformConfigAddress(program.programId),
       mint: mint.publicKey,
-      guestTokenAccount: guestTokenAccount,
-      platformTreasury: platformTreasuryATA,
+      guestTokenAccount: tokenAccount(guest.publicKey),
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
       tokenProgram: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId
     })
     .signers([guest])
-    .rpc({ commitment: "confirmed" });
+    .rpc();
 
-    console.log("💰 STEP 1: Payment escrow created (500 tokens)");
+    return { reservation, escrow, signature };
+  }
 
-    // STEP 2: Release payment to host
-    const hostTokenAccount = getAssociatedTokenAddressSync(
-      mint.publicKey,
-      host.publicKey,
-      false,
-      TOKEN_2022_PROGRAM_ID,
-      ASSOCIATED_TOKEN_PROGRAM_ID
-    );
+  it("Should emit the reservation and its funded escrow when booking", async () => {
+    const { reservation, escrow, signature } = await book();
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+
+    const events = emittedEvents(signature);
+    assert.deepEqual(events.map(event => event.name), ["ReservationCreated", "EscrowFunded"]);
+
+    // TEST: The reservation event carries the booked stay and the price quoted on-chain
+    const created = events[0].data;
+    assert.isTrue(created.reservation.equals(reservation));
+    assert.isTrue(created.listing.equals(listing));
+    assert.isTrue(created.guest.equals(guest.publicKey));
+    assert.isTrue(created.host.equals(host.publicKey));
+    assert.strictEqual(created.startDate.toNumber(), reservationAccount.startDate.toNumber());
+    assert.strictEqual(created.endDate.toNumber(), reservationAccount.endDate.toNumber());
+    assert.strictEqual(created.guestCount, 2);
+    assert.strictEqual(created.totalNights, NIGHTS);
+    assert.strictEqual(created.pricePerNight.toNumber(), PRICE);
+    assert.strictEqual(created.totalPrice.toNumber(), TOTAL);
+
+    // TEST: The escrow event carries the amount held and the platform fee
+    const funded = events[1].data;
+    assert.isTrue(funded.paymentEscrow.equals(escrow));
+    assert.isTrue(funded.reservation.equals(reservation));
+    assert.isTrue(funded.guest.equals(guest.publicKey));
+    assert.isTrue(funded.host.equals(host.publicKey));
+    assert.strictEqual(funded.amount.toNumber(), TOTAL);
+    assert.strictEqual(funded.platformFee.toNumber(), FEE);
+    assert.strictEqual(funded.releaseDate.toNumber(), reservationAccount.endDate.toNumber());
+  });
 
-    // Get balances BEFORE release
-    const platformTreasuryBefore = await provider.connection.getTokenAccountBalance(platformTreasuryATA);
-    let hostBalanceBefore = 0;
-    try {
-      const hostBalanceResult = await provider.connection.getTokenAccountBalance(hostTokenAccount);
-      hostBalanceBefore = parseInt(hostBalanceResult.value.amount);
-    } catch (error) {
-      hostBalanceBefore = 0; // Account doesn't exist yet
-    }
+  it("Should emit the amounts paid out when the escrow is released", async () => {
+    const { reservation, escrow } = await book();
+    const [host_pkey] = getHostAddress(host.publicKey, program.programId);
+    await program.methods.confirmReservation()
+    .accounts({
+      hostAuthority: host.publicKey,
+      host: host_pkey,
+      listing: listing,
+      reservation: reservation,
+    })
+    .signers([host])
+    .rpc();
 
-    await program.methods.releasePaymentEscrow()
+    const reservationAccount = await program.account.reservation.fetch(reservation);
+    warpTo(reservationAccount.startDate.toNumber());
+    await program.methods.checkIn()
+    .accounts({
+      authority: guest.publicKey,
+      reservation: reservation,
+    })
+    .signers([guest])
+    .rpc();
+
+    warpTo(reservationAccount.endDate.toNumber());
+    const signature = await program.methods.releasePaymentEscrow()
     .accounts({
       platformAuthority: platformAuthority.publicKey,
-      releasePaymentEscrow: escrow_pkey,
+      platformConfig: getPlatformConfigAddress(program.programId),
+      releasePaymentEscrow: escrow,
+      reservation: reservation,
+      host: host_pkey,
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
       mint: mint.publicKey,
-      platformTreasury: platformTreasuryATA,
-      hostTokenAccount: hostTokenAccount,
+      platformTreasury: platformTreasury,
+      hostTokenAccount: tokenAccount(host.publicKey),
       tokenProgram: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId,
     })
     .signers([platformAuthority])
-    .rpc({ commitment: "confirmed" });
-
-    console.log("🏠 STEP 2: Payment released to host");
-
-    // STEP 3: Verify final state
-    const escrowAfterRelease = await program.account.paymentEscrow.fetch(escrow_pkey);
-    const hostBalanceAfter = await provider.connection.getTokenAccountBalance(hostTokenAccount);
-    const platformTreasuryAfter = await provider.connection.getTokenAccountBalance(platformTreasuryATA);
+    .rpc();
+
+    const events = emittedEvents(signature);
+    assert.deepEqual(events.map(event => event.name), ["EscrowReleased"]);
+
+    // TEST: The host gets the stay, the treasury the platform fee, nothing is withheld
+    const released = events[0].data;
+    assert.isTrue(released.paymentEscrow.equals(escrow));
+    assert.isTrue(released.reservation.equals(reservation));
+    assert.isTrue(released.host.equals(host.publicKey));
+    assert.strictEqual(released.hostAmount.toNumber(), TOTAL - FEE);
+    assert.strictEqual(released.platformFee.toNumber(), FEE);
+    assert.strictEqual(released.penaltyWithheld.toNumber(), 0);
+  });
 
-    // ✅ VERIFY COMPLETE CYCLE
-    assert.strictEqual(Object.keys(escrowAfterRelease.status)[0], "released", "Escrow should be Released");
-    
-    const expectedHostAmount = 500 - 25; // 475 tokens after 5% platform fee
-    const hostBalanceChange = parseInt(hostBalanceAfter.value.amount) - hostBalanceBefore;
-    const platformBalanceChange = parseInt(platformTreasuryBefore.value.amount) - parseInt(platformTreasuryAfter.value.amount);
-    
-    assert.strictEqual(hostBalanceChange, expectedHostAmount, `Host should receive ${expectedHostAmount} tokens`);
+  it("Should emit the refund split when the guest cancels", async () => {
+    const { reservation, escrow } = await book();
 
-    console.log("🎉 COMPLETE PAYMENT CYCLE SUCCESS!");
-    console.log(`💰 Guest paid: 500 tokens`);
-    console.log(`🏠 Host received: ${hostBalanceChange} tokens`);
-    console.log(`🏢 Platform kept: 25 tokens (5% fee)`);
-    console.log(`💸 Transfer fee: ~${platformBalanceChange - hostBalanceChange} tokens`);
-    
-    // Guest final balance: 6950 - 500 = ~6450 tokens remaining
-    console.log("✅ Guest balance: ~6450 tokens remaining after complete cycle");
+    const signature = await program.methods.cancelReservation()
+    .accounts({
+      guestAuthority: guest.publicKey,
+      listing: listing,
+      host: getHostAddress(host.publicKey, program.programId)[0],
+      reservation: reservation,
+      availability: getAvailabilityAddress(listing, program.programId),
+      platformConfig: getPlatformConfigAddress(program.programId),
+      paymentEscrow: escrow,
+      escrowVault: getEscrowVaultAddress(escrow, program.programId),
+      mint: mint.publicKey,
+      guestTokenAccount: tokenAccount(guest.publicKey),
+      hostTokenAccount: tokenAccount(host.publicKey),
+      platformTreasury: platformTreasury,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([guest])
+    .rpc();
+
+    const events = emittedEvents(signature);
+    assert.deepEqual(events.map(event => event.name), ["ReservationCancelled"]);
+
+    // TEST: Still pending, so the whole stay is refunded and the platform keeps its fee
+    const cancelled = events[0].data;
+    assert.isTrue(cancelled.reservation.equals(reservation));
+    assert.isTrue(cancelled.listing.equals(listing));
+    assert.isTrue(cancelled.guest.equals(guest.publicKey));
+    assert.strictEqual(cancelled.refundBps, 10000);
+    assert.strictEqual(cancelled.refundAmount.toNumber(), TOTAL - FEE);
+    assert.strictEqual(cancelled.hostAmount.toNumber(), 0);
+    assert.strictEqual(cancelled.platformFee.toNumber(), FEE);
+    assert.strictEqual(cancelled.penaltyWithheld.toNumber(), 0);
   });
 });
 
@@ -941,6 +3125,56 @@ function getMintInfo(mint: PublicKey, connection: Connection) {
   return getMint(connection, mint, "confirmed", TOKEN_2022_PROGRAM_ID);
 }
 
+function getPlatformConfigAddress(programID: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(PLATFORM_CONFIG_SEED)],
+    programID
+  )[0];
+}
+
+function getProgramDataAddress(programID: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [programID.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID
+  )[0];
+}
+
+function getEscrowVaultAddress(paymentEscrow: PublicKey, programID: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(ESCROW_VAULT_SEED),
+      paymentEscrow.toBuffer()
+    ], programID)[0];
+}
+
+function getUserProfileAddress(author: PublicKey, programID: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(USER_PROFILE_SEED),
+      author.toBuffer()
+    ], programID)[0];
+}
+
+function getAvailabilityAddress(listing: PublicKey, programID: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(AVAILABILITY_SEED),
+      listing.toBuffer()
+    ], programID)[0];
+}
+
+function getBookingIndexAddress(listing: PublicKey, bookingCount: number, programID: PublicKey) {
+  const bookingCountBuffer = Buffer.alloc(8);
+  bookingCountBuffer.writeBigUInt64LE(BigInt(bookingCount), 0);
+
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(BOOKING_INDEX_SEED),
+      listing.toBuffer(),
+      bookingCountBuffer,
+    ], programID)[0];
+}
+
 function getHostAddress(author: PublicKey, programID: PublicKey) {
   return PublicKey.findProgramAddressSync(
     [
@@ -969,15 +3203,15 @@ function getGuestAddress(author: PublicKey, programID: PublicKey) {
     ], programID);
 }
 
-function getReservationAddress(author: PublicKey, reservationId: number, programID: PublicKey) {
-  const reservationIdBuffer = Buffer.alloc(8);
-  reservationIdBuffer.writeBigUInt64LE(BigInt(reservationId), 0);
+function getReservationAddress(author: PublicKey, reservationCount: number, programID: PublicKey) {
+  const reservationCountBuffer = Buffer.alloc(8);
+  reservationCountBuffer.writeBigUInt64LE(BigInt(reservationCount), 0);
 
   return PublicKey.findProgramAddressSync(
     [
       anchor.utils.bytes.utf8.encode(RESERVATION_SEED),
       author.toBuffer(),
-      reservationIdBuffer,
+      reservationCountBuffer,
     ], programID);
 }
 
@@ -993,6 +3227,14 @@ function getPaymentEscrowAddress(reservation: PublicKey, escrowId: number, progr
     ], programID);
 }
 
+function getDisputeAddress(paymentEscrow: PublicKey, programID: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(DISPUTE_SEED),
+      paymentEscrow.toBuffer()
+    ], programID)[0];
+}
+
 // Helper function to update frontend constants.ts file
 function updateFrontendConstants(guestPDA: string, mintPubkey: string, hostPDA: string, allListingPDAs: string[] = []) {
   try {
@@ -11,9 +11,7 @@ import usePaymentModal from "@/app/hooks/usePaymentModal";
 import React, { FC, useCallback, useEffect, useMemo, useState } from "react";
 import { categories } from "@/app/components/navbar/categories/CategoriesContainer";
 import PaymentModal from "@/app/components/modals/PaymentModal";
-import createReservation from "@/app/actions/anchor/createReservation";
-import createPaymentEscrow from "@/app/actions/anchor/createPaymentEscrow";
-import { CreateReservationResult } from "@/app/types/blockchain";
+import bookListing from "@/app/actions/anchor/bookListing";
 
 const initialRange = {
   startDate: new Date(),
@@ -44,10 +42,6 @@ const ListingClient: FC<ListingClientProps> = ({
   const [localReservations, setLocalReservations] = useState(reservations);
   const [loading, setLoading] = useState(false);
 
-  const [reservationData, setReservationData] =
-    useState<CreateReservationResult | null>(null);
-  const [reservationLoading, setReservationLoading] = useState(false);
-
   const disabledDates = useMemo(() => {
     let dates: Date[] = [];
     localReservations.forEach((reservation: any) => {
@@ -79,57 +73,34 @@ const ListingClient: FC<ListingClientProps> = ({
     paymentModal.onOpen();
   }, [paymentModal]);
 
-  useEffect(() => {
-    const autoCreateReservation = async () => {
-      if (paymentModal.isOpen && !reservationData && !reservationLoading) {
-        setReservationLoading(true);
-
-        try {
-          console.log("🚀 Auto-creating reservation...");
-          const result = await createReservation({
-            listingId: listing.id,
-            userId: "wallet-user", // We'll use wallet address
-            authorId: "blockchain-auth",
-          });
-          setReservationData(result);
-          console.log("✅ Reservation created automatically:", result);
-        } catch (err: any) {
-          console.error("❌ Auto-reservation failed:", err);
-          toast.error(`Failed to create reservation: ${err.message}`);
-        } finally {
-          setReservationLoading(false);
-        }
-      }
-    };
-
-    autoCreateReservation();
-  }, [paymentModal.isOpen, reservationData, reservationLoading, listing.id]);
-
+  // The reservation and its payment go out as one transaction, so nothing is
+  // reserved unless the escrow is funded
   const handleFinalPayment = useCallback(async (): Promise<void> => {
-    if (!reservationData) {
-      throw new Error("No reservation data available for payment");
-    }
+    const startDate = Math.floor(
+      (dateRange.startDate || new Date()).getTime() / 1000
+    );
+    const endDate = Math.floor(
+      (dateRange.endDate || new Date()).getTime() / 1000
+    );
 
     try {
-      console.log("💳 Processing final payment escrow...");
-
-      // Call the blockchain payment escrow
-      const escrowResult = await createPaymentEscrow({
-        reservationPDA: reservationData.reservationPDA,
-        amount: reservationData.details.totalPrice,
-        releaseDate: reservationData.details.endDate + 24 * 60 * 60, // Release 1 day after checkout
+      console.log("💳 Booking listing...");
+
+      // Nights and price are computed on-chain from the selected dates
+      const result = await bookListing({
+        listingId: listing.id,
+        startDate,
+        endDate,
+        releaseDate: endDate + 24 * 60 * 60, // Release 1 day after checkout
         escrowId: Date.now(), // Use timestamp as unique escrow ID
       });
 
-      console.log("✅ Payment escrow completed:", escrowResult);
-
-      // Reset reservation data for next use
-      setReservationData(null);
+      console.log("✅ Listing booked:", result);
     } catch (error) {
-      console.error("Final payment failed:", error);
+      console.error("Booking failed:", error);
       throw error;
     }
-  }, [reservationData]);
+  }, [listing.id, dateRange]);
 
   useEffect(() => {
     if (dateRange.startDate && dateRange.endDate) {
@@ -177,8 +148,6 @@ const ListingClient: FC<ListingClientProps> = ({
           ) || 1
         }
         onPayment={handleFinalPayment}
-        reservationData={reservationData}
-        reservationLoading={reservationLoading}
       />
     </ClientOnly>
   );
@@ -1,7 +1,6 @@
 import React from "react";
 
 import getCurrentListing from "@/app/actions/anchor/getCurrentListing";
-import createReservation from "@/app/actions/anchor/createReservation";
 import getCurrentUser from "@/app/actions/anchor/getCurrentUser";
 
 import ListingClient from "./ListingClient";
@@ -13,7 +12,6 @@ interface IParams {
 
 const page = async ({ params }: { params: IParams }) => {
   const listing = await getCurrentListing(params);
-  const reservations = await createReservation(params);
   const currentUser = await getCurrentUser();
 
   if (listing == null) return null;
@@ -23,7 +21,7 @@ const page = async ({ params }: { params: IParams }) => {
       <ListingClient
         currentUser={currentUser ?? null}
         listing={listing ?? undefined}
-        reservations={Array.isArray(reservations) ? reservations : []}
+        reservations={[]}
       />
     </ClientOnly>
   );
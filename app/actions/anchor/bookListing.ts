@@ -0,0 +1,219 @@
+import { Connection, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  listingPDA_1,
+  RPC,
+  GUEST_SEED,
+  RESERVATION_SEED,
+  USER_PROFILE_SEED,
+  AVAILABILITY_SEED,
+  BOOKING_INDEX_SEED,
+  PAYMENT_ESCROW_SEED,
+  PLATFORM_CONFIG_SEED,
+  ESCROW_VAULT_SEED,
+  PROGRAM_ID,
+  mintPubkey as mintPubkeyString,
+} from "@/app/actions/anchor/constants";
+import {
+  BookListingParams,
+  BookListingResult,
+  ReservationStatus,
+  PaymentStatus,
+} from "@/app/types/blockchain";
+import {
+  BN,
+  Program,
+  AnchorProvider,
+  setProvider,
+  Idl,
+} from "@coral-xyz/anchor";
+import idl from "../../../airbnb-blockhain/target/idl/airbnb_blockhain.json";
+
+// Reserve the dates and fund the escrow in a single transaction, so a failed
+// payment never leaves the nights held by an unpaid reservation
+export default async function bookListing(
+  params: BookListingParams
+): Promise<BookListingResult> {
+  try {
+    const {
+      listingId,
+      startDate,
+      endDate,
+      guestCount = 1,
+      releaseDate,
+      escrowId,
+    } = params;
+
+    // Check wallet connection
+    if (!window.solana?.isPhantom) {
+      throw new Error("Wallet not connected");
+    }
+
+    const connection = new Connection(RPC, "confirmed");
+    const wallet = window.solana;
+    const guestAuthority = new PublicKey(wallet.publicKey!.toBytes());
+
+    const provider = new AnchorProvider(connection, wallet as any, {
+      commitment: "confirmed",
+    });
+    setProvider(provider);
+
+    const program = new Program(idl as Idl, provider) as Program;
+    const programId = new PublicKey(PROGRAM_ID);
+    const listingPubkey = new PublicKey(listingId || listingPDA_1);
+
+    const [userProfilePDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from(USER_PROFILE_SEED), guestAuthority.toBuffer()],
+      programId
+    );
+    const [guestPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from(GUEST_SEED), guestAuthority.toBuffer()],
+      programId
+    );
+    const [availabilityPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from(AVAILABILITY_SEED), listingPubkey.toBuffer()],
+      programId
+    );
+
+    // The reservation PDA is seeded by the guest's reservation counter and the
+    // booking index entry by the listing's booking counter
+    const guestAccount = await (program.account as any).guest.fetch(guestPDA);
+    const listingAccount = await (program.account as any).listing.fetch(
+      listingPubkey
+    );
+    const reservationId = guestAccount.reservationCount.toNumber();
+
+    const reservationCountBuffer = Buffer.alloc(8);
+    reservationCountBuffer.writeBigUInt64LE(BigInt(reservationId), 0);
+    const [reservationPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(RESERVATION_SEED),
+        guestAuthority.toBuffer(),
+        reservationCountBuffer,
+      ],
+      programId
+    );
+
+    const bookingCountBuffer = Buffer.alloc(8);
+    bookingCountBuffer.writeBigUInt64LE(
+      BigInt(listingAccount.bookingCount.toString()),
+      0
+    );
+    const [bookingIndexPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(BOOKING_INDEX_SEED),
+        listingPubkey.toBuffer(),
+        bookingCountBuffer,
+      ],
+      programId
+    );
+
+    const escrowIdBuffer = Buffer.alloc(8);
+    escrowIdBuffer.writeBigUInt64LE(BigInt(escrowId), 0);
+    const [paymentEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(PAYMENT_ESCROW_SEED),
+        reservationPDA.toBuffer(),
+        escrowIdBuffer,
+      ],
+      programId
+    );
+
+    // Funds are held in a vault owned by the escrow PDA until release or refund
+    const [escrowVaultPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from(ESCROW_VAULT_SEED), paymentEscrowPDA.toBuffer()],
+      programId
+    );
+
+    // Fee and accepted mint come from the platform config
+    const [platformConfigPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from(PLATFORM_CONFIG_SEED)],
+      programId
+    );
+
+    const mintPubkey = new PublicKey(mintPubkeyString);
+    const guestTokenAccount = getAssociatedTokenAddressSync(
+      mintPubkey,
+      guestAuthority,
+      false,
+      TOKEN_2022_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+
+    console.log("🏨 Booking listing with:");
+    console.log(`   Reservation ID: ${reservationId}`);
+    console.log(`   Guest: ${guestAuthority.toString()}`);
+    console.log(`   Listing: ${listingPubkey.toString()}`);
+    console.log(`   Reservation PDA: ${reservationPDA.toString()}`);
+    console.log(`   Escrow PDA: ${paymentEscrowPDA.toString()}`);
+
+    // The amount paid is the price the program quotes for these nights
+    const txId = await program.methods
+      .bookListing(
+        new BN(escrowId.toString()),
+        new BN(startDate),
+        new BN(endDate),
+        guestCount,
+        new BN(releaseDate)
+      )
+      .accounts({
+        guestAuthority: guestAuthority,
+        userProfile: userProfilePDA,
+        guest: guestPDA,
+        listing: listingPubkey,
+        host: listingAccount.host,
+        availability: availabilityPDA,
+        reservation: reservationPDA,
+        bookingIndex: bookingIndexPDA,
+        paymentEscrow: paymentEscrowPDA,
+        platformConfig: platformConfigPDA,
+        mint: mintPubkey,
+        guestTokenAccount: guestTokenAccount,
+        escrowVault: escrowVaultPDA,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    // Nights, price and fee are computed by the program, read them back
+    const reservationAccount = await (
+      program.account as any
+    ).reservation.fetch(reservationPDA);
+
+    const result: BookListingResult = {
+      success: true,
+      reservationId,
+      reservationPDA: reservationPDA.toString(),
+      escrowPDA: paymentEscrowPDA.toString(),
+      transactionId: txId,
+      details: {
+        guest: reservationAccount.guest.toString(),
+        host: reservationAccount.host.toString(),
+        listing: reservationAccount.listing.toString(),
+        startDate: reservationAccount.startDate.toNumber(),
+        endDate: reservationAccount.endDate.toNumber(),
+        guestCount: reservationAccount.guestCount,
+        totalNights: reservationAccount.totalNights,
+        pricePerNight: reservationAccount.pricePerNight.toNumber(),
+        totalPrice: reservationAccount.totalPrice.toNumber(),
+        status: ReservationStatus.PENDING,
+        paymentStatus: PaymentStatus.PAID,
+        createdAt: reservationAccount.createdAt.toNumber(),
+        paymentEscrow: paymentEscrowPDA.toString(),
+        tokenAmount: reservationAccount.tokenAmount.toNumber(),
+        platformFee: reservationAccount.platformFee.toNumber(),
+      },
+    };
+
+    console.log("✅ Listing booked:", txId);
+    return result;
+  } catch (error: any) {
+    console.error("❌ Error booking listing:", error);
+    throw new Error(`Failed to book listing: ${error.message}`);
+  }
+}
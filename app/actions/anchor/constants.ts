@@ -24,6 +24,11 @@ export const LISTING_SEED = "LISTING_SEED";
 export const RESERVATION_SEED = "RESERVATION_SEED";
 export const PAYMENT_ESCROW_SEED = "PAYMENT_ESCROW_SEED";
 export const PLATFORM_TREASURY_SEED = "PLATFORM_TREASURY_SEED";
+export const PLATFORM_CONFIG_SEED = "PLATFORM_CONFIG_SEED";
+export const ESCROW_VAULT_SEED = "ESCROW_VAULT_SEED";
+export const USER_PROFILE_SEED = "USER_PROFILE_SEED";
+export const AVAILABILITY_SEED = "AVAILABILITY_SEED";
+export const BOOKING_INDEX_SEED = "BOOKING_INDEX_SEED";
 export const PROGRAM_ID = "5FeA9qBzmvEDreexhEMmivcz9KccuhCZaqWWVYxtkgm9"; // devnet
 
 // Helper function to get all listing PDAs
@@ -48,15 +53,13 @@ export const parseString = (
   return { value, nextOffset: offset + 4 + length };
 };
 
-export const parseGuestAccount = (accountData: Buffer) => {
+export const parseUserProfileAccount = (accountData: Buffer) => {
   // Skip first 8 bytes (discriminator)
   const data = accountData.slice(8);
   let offset = 0;
 
-  // Parse guest_author (32 bytes)
-  const guest_author = new PublicKey(
-    data.slice(offset, offset + 32)
-  ).toString();
+  // Parse authority (32 bytes)
+  const authority = new PublicKey(data.slice(offset, offset + 32)).toString();
   offset += 32;
 
   // Parse name (4 bytes length + string data)
@@ -64,52 +67,90 @@ export const parseGuestAccount = (accountData: Buffer) => {
   const name = nameResult.value;
   offset = nameResult.nextOffset;
 
-  // Parse email (4 bytes length + string data)
-  const emailResult = parseString(data, offset);
-  const email = emailResult.value;
-  offset = emailResult.nextOffset;
-
   // Parse image_url (4 bytes length + string data)
   const imageResult = parseString(data, offset);
   const image_url = imageResult.value;
   offset = imageResult.nextOffset;
 
-  // Parse hashed_password (4 bytes length + string data)
-  const passwordResult = parseString(data, offset);
-  const hashed_password = passwordResult.value;
-  offset = passwordResult.nextOffset;
+  // Parse profile_commitment (32 bytes), hash of the off-chain profile
+  const profile_commitment = data.slice(offset, offset + 32).toString("hex");
+  offset += 32;
+
+  // Parse metadata_uri (4 bytes length + string data)
+  const metadataResult = parseString(data, offset);
+  const metadata_uri = metadataResult.value;
+  offset = metadataResult.nextOffset;
+
+  // Parse preferred_language (4 bytes length + string data)
+  const languageResult = parseString(data, offset);
+  const preferred_language = languageResult.value;
+  offset = languageResult.nextOffset;
+
+  // Parse roles (1 byte)
+  const roles = data.readUInt8(offset);
+  offset += 1;
 
   // Parse created_at (8 bytes)
   const created_at = data.readBigUInt64LE(offset);
   offset += 8;
 
-  // Parse phone_number (4 bytes length + string data)
-  const phoneResult = parseString(data, offset);
-  const phone_number = phoneResult.value;
-  offset = phoneResult.nextOffset;
+  // Parse updated_at (8 bytes)
+  const updated_at = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  // Parse bump (1 byte)
+  const bump = data.readUInt8(offset);
+
+  return {
+    authority,
+    name,
+    image_url,
+    profile_commitment,
+    metadata_uri,
+    preferred_language,
+    roles,
+    created_at: Number(created_at),
+    updated_at: Number(updated_at),
+    bump,
+  };
+};
+
+export const parseGuestAccount = (accountData: Buffer) => {
+  // Skip first 8 bytes (discriminator)
+  const data = accountData.slice(8);
+  let offset = 0;
+
+  // Parse guest_author (32 bytes)
+  const guest_author = new PublicKey(
+    data.slice(offset, offset + 32)
+  ).toString();
+  offset += 32;
+
+  // Parse profile (32 bytes), name and picture live on the user profile
+  const profile = new PublicKey(data.slice(offset, offset + 32)).toString();
+  offset += 32;
 
-  // Parse date_of_birth (8 bytes)
-  const date_of_birth = data.readBigUInt64LE(offset);
+  // Parse created_at (8 bytes)
+  const created_at = data.readBigUInt64LE(offset);
   offset += 8;
 
-  // Parse preferred_language (4 bytes length + string data)
-  const languageResult = parseString(data, offset);
-  const preferred_language = languageResult.value;
-  offset = languageResult.nextOffset;
+  // Parse reservation_count (8 bytes)
+  const reservation_count = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  // Parse open_reservation_count (8 bytes)
+  const open_reservation_count = data.readBigUInt64LE(offset);
+  offset += 8;
 
   // Parse bump (1 byte)
   const bump = data.readUInt8(offset);
 
   return {
     guest_author,
-    name,
-    email,
-    image_url,
-    hashed_password,
+    profile,
     created_at: Number(created_at),
-    phone_number,
-    date_of_birth: Number(date_of_birth),
-    preferred_language,
+    reservation_count: Number(reservation_count),
+    open_reservation_count: Number(open_reservation_count),
     bump,
   };
 };
@@ -142,6 +183,10 @@ export const parseListingAccount = (accountData: Buffer) => {
   const created_at = data.readBigUInt64LE(offset);
   offset += 8;
 
+  // Parse updated_at (8 bytes)
+  const updated_at = data.readBigUInt64LE(offset);
+  offset += 8;
+
   // Parse category (4 bytes length + string data)
   const categoryResult = parseString(data, offset);
   const category = categoryResult.value;
@@ -174,6 +219,24 @@ export const parseListingAccount = (accountData: Buffer) => {
 
   // Parse price (8 bytes)
   const price = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  // Parse cancellation_policy (1 byte variant + 4 byte hours, NonRefundable has no hours)
+  const policyVariant = data.readUInt8(offset);
+  offset += 1;
+  const policyNames = ["flexible", "moderate", "strict", "nonRefundable"];
+  const cancellation_policy = {
+    kind: policyNames[policyVariant],
+    hours: policyVariant < 3 ? data.readUInt32LE(offset) : 0,
+  };
+  if (policyVariant < 3) offset += 4;
+
+  // Parse booking_count (8 bytes)
+  const booking_count = data.readBigUInt64LE(offset);
+  offset += 8;
+
+  // Parse open_reservation_count (8 bytes)
+  const open_reservation_count = data.readBigUInt64LE(offset);
 
   return {
     host,
@@ -181,6 +244,7 @@ export const parseListingAccount = (accountData: Buffer) => {
     description,
     image_url,
     created_at: Number(created_at),
+    updated_at: Number(updated_at),
     category,
     room_count,
     bathroom_count,
@@ -189,5 +253,8 @@ export const parseListingAccount = (accountData: Buffer) => {
     total_bookings: Number(total_bookings),
     is_active,
     price: Number(price),
+    cancellation_policy,
+    booking_count: Number(booking_count),
+    open_reservation_count: Number(open_reservation_count),
   };
 };
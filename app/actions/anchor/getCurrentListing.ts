@@ -34,7 +34,7 @@ export default async function currentListing(params: IParams) {
       title: listingData.title,
       description: listingData.description,
       imageSrc: listingData.image_url,
-      createdAt: new Date(listingData.created_at).toISOString(),
+      createdAt: new Date(listingData.created_at * 1000).toISOString(),
       category: listingData.category,
       roomCount: listingData.room_count,
       bathroomCount: listingData.bathroom_count,
@@ -47,8 +47,8 @@ export default async function currentListing(params: IParams) {
         name: "Blockchain Host", // TODO: Get from guest account
         email: null,
         image: null,
-        createdAt: new Date(listingData.created_at).toISOString(),
-        updatedAt: new Date(listingData.created_at).toISOString(),
+        createdAt: new Date(listingData.created_at * 1000).toISOString(),
+        updatedAt: new Date(listingData.created_at * 1000).toISOString(),
         emailVerified: null,
         hashedPassword: null,
         favoriteIds: [],
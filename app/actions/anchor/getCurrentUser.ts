@@ -1,6 +1,11 @@
 // TODO: take a look about sessions
 import { Connection, PublicKey } from "@solana/web3.js";
-import { guestPDA, RPC, parseGuestAccount } from "./constants";
+import {
+  guestPDA,
+  RPC,
+  parseGuestAccount,
+  parseUserProfileAccount,
+} from "./constants";
 
 export default async function getCurrentUser() {
   const connection = new Connection(RPC, "confirmed");
@@ -19,8 +24,16 @@ export default async function getCurrentUser() {
       const guestData = parseGuestAccount(guestAccount.data);
 
       console.log("Parsed Guest Data:", guestData);
-      console.log("Guest Name:", guestData.name);
-      console.log("Guest Email:", guestData.email);
+
+      // Name and picture are stored on the user profile the guest role points at
+      const profileAccount = await connection.getAccountInfo(
+        new PublicKey(guestData.profile),
+        "confirmed"
+      );
+      if (profileAccount) {
+        const profileData = parseUserProfileAccount(profileAccount.data);
+        console.log("Guest Name:", profileData.name);
+      }
     } else {
       console.log("Guest account not found");
     }
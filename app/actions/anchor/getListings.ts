@@ -50,7 +50,7 @@ export default async function getListings_(searchParams: any) {
             title: listingData.title,
             description: listingData.description,
             imageSrc: listingData.image_url,
-            createdAt: new Date(listingData.created_at).toISOString(),
+            createdAt: new Date(listingData.created_at * 1000).toISOString(),
             category: listingData.category,
             roomCount: listingData.room_count,
             bathroomCount: listingData.bathroom_count,
@@ -10,7 +10,6 @@ import Heading from "../Heading";
 import { BiMoney } from "react-icons/bi";
 import { MdDateRange } from "react-icons/md";
 import { BsPeople } from "react-icons/bs";
-import { CreateReservationResult } from "@/app/types/blockchain";
 
 interface PaymentModalProps {
   totalPrice: number;
@@ -20,9 +19,7 @@ interface PaymentModalProps {
   listingTitle: string;
   pricePerNight: number;
   totalNights: number;
-  onPayment: () => Promise<void>; // Final payment processing
-  reservationData: CreateReservationResult | null;
-  reservationLoading: boolean;
+  onPayment: () => Promise<void>; // Books the dates and pays in one transaction
 }
 
 const PaymentModal: React.FC<PaymentModalProps> = ({
@@ -34,27 +31,16 @@ const PaymentModal: React.FC<PaymentModalProps> = ({
   pricePerNight,
   totalNights,
   onPayment,
-  reservationData,
-  reservationLoading,
 }) => {
   const paymentModal = usePaymentModal();
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string>("");
 
   const submitHandler = async () => {
-    if (!reservationData) {
-      setError("Reservation not ready. Please try again.");
-      return;
-    }
-
     setError("");
     setLoading(true);
 
     try {
-      console.log(
-        "💳 Processing payment for reservation:",
-        reservationData.reservationPDA
-      );
       await onPayment();
       toast.success("Payment successful! Reservation confirmed.");
       paymentModal.onClose();
@@ -138,31 +124,6 @@ const PaymentModal: React.FC<PaymentModalProps> = ({
         </div>
       </div>
 
-      {/* Reservation Status */}
-      {reservationLoading && (
-        <div className="p-3 bg-blue-50 border border-blue-200 rounded-lg mb-4">
-          <p className="text-sm text-blue-600">
-            Creating reservation on blockchain...
-          </p>
-        </div>
-      )}
-
-      {reservationData && (
-        <div className="p-3 bg-green-50 border border-green-200 rounded-lg mb-4">
-          <p className="text-sm text-green-600">
-            ✅ Reservation created! ID: {reservationData.reservationId}
-          </p>
-          <p className="text-xs text-green-500 mt-1">
-            PDA: {reservationData.reservationPDA.slice(0, 8)}...
-            {reservationData.reservationPDA.slice(-8)}
-          </p>
-          <p className="text-xs text-gray-500 mt-1">
-            Status: {reservationData.details.status} • Payment:{" "}
-            {reservationData.details.paymentStatus}
-          </p>
-        </div>
-      )}
-
       {/* Error Display */}
       {error && (
         <div className="p-3 bg-red-50 border border-red-200 rounded-lg mb-4">
@@ -183,14 +144,12 @@ const PaymentModal: React.FC<PaymentModalProps> = ({
       isOpen={paymentModal.isOpen}
       label="Payment"
       body={paymentBody}
-      buttonLabel={
-        reservationLoading
-          ? "Creating Reservation..."
-          : `Confirm Payment ($${totalPrice + Math.round(totalPrice * 0.05)})`
-      }
+      buttonLabel={`Confirm Payment ($${
+        totalPrice + Math.round(totalPrice * 0.05)
+      })`}
       onSubmit={submitHandler}
       close={paymentModal.onClose}
-      buttonLoading={loading || reservationLoading}
+      buttonLoading={loading}
     />
   );
 };
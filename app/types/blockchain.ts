@@ -49,10 +49,12 @@ declare global {
   }
 }
 
-export interface PaymentEscrowParams {
-  reservationPDA: string;
-  amount: number;
-  releaseDate: number;
+export interface BookListingParams {
+  listingId?: string;
+  startDate: number; // Unix timestamp of check-in
+  endDate: number; // Unix timestamp of check-out
+  guestCount?: number;
+  releaseDate: number; // Unix timestamp the host can be paid from
   escrowId: number;
 }
 
@@ -74,10 +76,12 @@ export interface ReservationDetails {
   platformFee?: number; // u64 - optional for frontend
 }
 
-export interface CreateReservationResult {
+export interface BookListingResult {
   success: boolean;
   reservationId: number; // The ID used to generate PDA
   reservationPDA: string; // The generated PDA as string
+  escrowPDA: string; // The generated escrow PDA
+  transactionId: string; // Signature of the booking transaction
   details: ReservationDetails;
 }
 
@@ -93,10 +97,3 @@ export interface PaymentEscrowData {
   releaseDate: number; // u64
   bump: number; // u8
 }
-
-export interface CreatePaymentEscrowResult {
-  success: boolean;
-  escrowPDA: string; // The generated escrow PDA
-  transactionId?: string; // Optional transaction signature
-  escrowData: PaymentEscrowData;
-}